@@ -0,0 +1,233 @@
+// Copyright 2016-2018 Mateusz Sieczko and other GilRs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Traits that let other input sources be used in place of the native platform.
+//!
+//! [`Gilrs`](crate::Gilrs), [`Gamepad`](crate::Gamepad) and [`FfDevice`](crate::FfDevice)
+//! implement them for the platform gilrs-core was compiled for. Custom backend (network
+//! controllers, recorded input, tests) can implement them too. Backend that reports elements using
+//! codes from [`native_ev_codes`](crate::native_ev_codes) will get the same default mapping as
+//! native gamepads.
+
+use std::fmt::Debug;
+use std::io;
+use std::time::Duration;
+
+use crate::{AxisInfo, Capabilities, DeviceSubtype, EvCode, Event, PowerInfo};
+
+/// `Send` on all platforms except web, where native backend can't be sent between threads.
+#[cfg(not(target_arch = "wasm32"))]
+pub trait MaybeSend: Send {}
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: Send + ?Sized> MaybeSend for T {}
+
+/// `Send` on all platforms except web, where native backend can't be sent between threads.
+#[cfg(target_arch = "wasm32")]
+pub trait MaybeSend {}
+#[cfg(target_arch = "wasm32")]
+impl<T: ?Sized> MaybeSend for T {}
+
+/// Source of gamepads and their events.
+///
+/// Gamepads are identified by index. Index of gamepad must not change and `gamepad()` has to
+/// return `Some` for all indexes smaller than `last_gamepad_hint()`. `Connected` event for new
+/// gamepad must use index equal to number of gamepads seen so far, and other events can only be
+/// reported for gamepads that were connected before. `AxisValueChanged` events can only use
+/// codes for which gamepad's `axis_info()` returns `Some`. `gilrs` logs an error and skips events
+/// that break these rules.
+pub trait GamepadBackend: Debug + MaybeSend {
+    /// Returns oldest event or `None` if all events were processed.
+    fn next_event(&mut self) -> Option<Event>;
+
+    /// Returns oldest event, waiting for new event if necessary.
+    fn next_event_blocking(&mut self, timeout: Option<Duration>) -> Option<Event>;
+
+    /// Borrows gamepad or returns `None` if index is invalid.
+    fn gamepad(&self, id: usize) -> Option<&dyn BackendGamepad>;
+
+    /// Returns id greater than id of last connected gamepad.
+    fn last_gamepad_hint(&self) -> usize;
+
+    /// Returns false if no more events will be returned, for example because thread that reads
+    /// them stopped.
+    fn is_alive(&self) -> bool {
+        true
+    }
+
+    /// Returns how often power information is refreshed in the background or `None` if backend
+    /// doesn't do that.
+    fn power_info_interval(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Information about single gamepad provided by [`GamepadBackend`].
+pub trait BackendGamepad: Debug {
+    /// Returns name of gamepad.
+    fn name(&self) -> &str;
+
+    /// Returns true if gamepad is connected.
+    fn is_connected(&self) -> bool;
+
+    /// Returns SDL2 compatible UUID. See [`Gamepad::uuid()`](crate::Gamepad::uuid).
+    fn uuid(&self) -> [u8; 16];
+
+    fn mount_point(&self) -> Option<String> {
+        None
+    }
+
+    fn vendor_id(&self) -> Option<u16> {
+        None
+    }
+
+    fn product_id(&self) -> Option<u16> {
+        None
+    }
+
+    fn version(&self) -> Option<u16> {
+        None
+    }
+
+    /// See [`Gamepad::set_player_index()`](crate::Gamepad::set_player_index).
+    fn set_player_index(&self, _index: Option<u8>) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    fn power_info(&self) -> PowerInfo {
+        PowerInfo::Unknown
+    }
+
+    fn is_ff_supported(&self) -> bool {
+        false
+    }
+
+    /// Creates device that will receive force feedback state. It's only used if
+    /// `is_ff_supported()` returns true.
+    fn ff_device(&self) -> Option<Box<dyn BackendFfDevice>> {
+        None
+    }
+
+    /// Returns codes that may appear in button related events.
+    fn buttons(&self) -> &[EvCode];
+
+    /// Returns codes that may appear in axis related events.
+    fn axes(&self) -> &[EvCode];
+
+    /// Returns information about axis. Every code returned from `axes()` and every code used in
+    /// `AxisValueChanged` event must have one.
+    fn axis_info(&self, nec: EvCode) -> Option<&AxisInfo>;
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            has_rumble: self.is_ff_supported(),
+            num_buttons: self.buttons().len(),
+            num_axes: self.axes().len(),
+            subtype: DeviceSubtype::Unknown,
+            ..Default::default()
+        }
+    }
+}
+
+/// Force feedback output of [`BackendGamepad`].
+pub trait BackendFfDevice: Debug + MaybeSend {
+    /// Sets magnitude for strong and weak ff motors.
+    fn set_ff_state(&mut self, strong: u16, weak: u16, min_duration: Duration);
+}
+
+impl GamepadBackend for crate::Gilrs {
+    fn next_event(&mut self) -> Option<Event> {
+        crate::Gilrs::next_event(self)
+    }
+
+    fn next_event_blocking(&mut self, timeout: Option<Duration>) -> Option<Event> {
+        crate::Gilrs::next_event_blocking(self, timeout)
+    }
+
+    fn gamepad(&self, id: usize) -> Option<&dyn BackendGamepad> {
+        crate::Gilrs::gamepad(self, id).map(|gp| gp as &dyn BackendGamepad)
+    }
+
+    fn last_gamepad_hint(&self) -> usize {
+        crate::Gilrs::last_gamepad_hint(self)
+    }
+
+    fn is_alive(&self) -> bool {
+        crate::Gilrs::is_alive(self)
+    }
+
+    fn power_info_interval(&self) -> Option<Duration> {
+        crate::Gilrs::power_info_interval(self)
+    }
+}
+
+impl BackendGamepad for crate::Gamepad {
+    fn name(&self) -> &str {
+        crate::Gamepad::name(self)
+    }
+
+    fn is_connected(&self) -> bool {
+        crate::Gamepad::is_connected(self)
+    }
+
+    fn uuid(&self) -> [u8; 16] {
+        crate::Gamepad::uuid(self)
+    }
+
+    fn mount_point(&self) -> Option<String> {
+        crate::Gamepad::mount_point(self)
+    }
+
+    fn vendor_id(&self) -> Option<u16> {
+        crate::Gamepad::vendor_id(self)
+    }
+
+    fn product_id(&self) -> Option<u16> {
+        crate::Gamepad::product_id(self)
+    }
+
+    fn version(&self) -> Option<u16> {
+        crate::Gamepad::version(self)
+    }
+
+    fn set_player_index(&self, index: Option<u8>) -> io::Result<()> {
+        crate::Gamepad::set_player_index(self, index)
+    }
+
+    fn power_info(&self) -> PowerInfo {
+        crate::Gamepad::power_info(self)
+    }
+
+    fn is_ff_supported(&self) -> bool {
+        crate::Gamepad::is_ff_supported(self)
+    }
+
+    fn ff_device(&self) -> Option<Box<dyn BackendFfDevice>> {
+        crate::Gamepad::ff_device(self).map(|dev| Box::new(dev) as Box<dyn BackendFfDevice>)
+    }
+
+    fn buttons(&self) -> &[EvCode] {
+        crate::Gamepad::buttons(self)
+    }
+
+    fn axes(&self) -> &[EvCode] {
+        crate::Gamepad::axes(self)
+    }
+
+    fn axis_info(&self, nec: EvCode) -> Option<&AxisInfo> {
+        crate::Gamepad::axis_info(self, nec)
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        crate::Gamepad::capabilities(self)
+    }
+}
+
+impl BackendFfDevice for crate::FfDevice {
+    fn set_ff_state(&mut self, strong: u16, weak: u16, min_duration: Duration) {
+        crate::FfDevice::set_ff_state(self, strong, weak, min_duration)
+    }
+}
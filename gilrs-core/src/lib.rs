@@ -6,9 +6,11 @@ use std::fmt::Display;
 use std::fmt::Formatter;
 
 use std::error;
+use std::io;
 use std::time::Duration;
 use std::time::SystemTime;
 
+pub mod backend;
 mod platform;
 pub mod utils;
 
@@ -54,7 +56,29 @@ pub enum EventType {
     ButtonReleased(EvCode),
     AxisValueChanged(i32, EvCode),
     Connected,
-    Disconnected,
+    Disconnected(DisconnectReason),
+    /// Power supply state of gamepad changed. Only reported by backends that can refresh it in
+    /// the background, see [`Gilrs::set_power_info_interval()`].
+    PowerChanged(PowerInfo),
+}
+
+/// Reason why gamepad was disconnected.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DisconnectReason {
+    /// Platform didn't say why gamepad was disconnected.
+    Unknown,
+    /// Device was removed from the system.
+    ///
+    /// Most platforms report devices turned off by USB power saving or by system going to sleep
+    /// the same way as unplugged ones, so they also end up here.
+    Removed,
+    /// Backend failed to communicate with device. Contains platform specific error code, for
+    /// example value returned by `XInputGetState()`.
+    Error(u32),
+    /// Other device took place of this gamepad, for example after XInput slots were renumbered.
+    /// Gamepad that is connected next with the same ID is a different device.
+    Replaced,
 }
 
 /// Holds information about expected axis range and deadzone.
@@ -65,10 +89,6 @@ pub struct AxisInfo {
     pub deadzone: Option<u32>,
 }
 
-/// State of device's power supply.
-///
-/// Battery level is reported as integer between 0 and 100.
-///
 /// ## Example
 ///
 /// ```
@@ -81,18 +101,46 @@ pub struct AxisInfo {
 ///     _ => (),
 /// };
 /// ```
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-pub enum PowerInfo {
-    /// Failed to determine power status.
+pub use gilrs_types::PowerInfo;
+
+/// Kind of device, as reported by platform.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum DeviceSubtype {
+    /// Platform doesn't report device kind.
+    #[default]
     Unknown,
-    /// Device doesn't have battery.
-    Wired,
-    /// Device is running on the battery.
-    Discharging(u8),
-    /// Battery is charging.
-    Charging(u8),
-    /// Battery is charged.
-    Charged,
+    Gamepad,
+    Wheel,
+    ArcadeStick,
+    FlightStick,
+    DancePad,
+    Guitar,
+    DrumKit,
+    ArcadePad,
+}
+
+/// Features of gamepad, queried once when it's connected.
+///
+/// Flags are `false` if platform can't tell whether device has given feature.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub struct Capabilities {
+    /// Device has rumble motors.
+    pub has_rumble: bool,
+    /// Device has separate motors in triggers.
+    pub has_trigger_rumble: bool,
+    /// Device has LED that can be controlled.
+    pub has_led: bool,
+    /// Device has gyroscope.
+    pub has_gyro: bool,
+    /// Device has touchpad.
+    pub has_touchpad: bool,
+    /// Number of buttons, same as length of [`Gamepad::buttons()`].
+    pub num_buttons: usize,
+    /// Number of axes, same as length of [`Gamepad::axes()`].
+    pub num_axes: usize,
+    pub subtype: DeviceSubtype,
 }
 
 /// Struct used to manage gamepads and retrieve events.
@@ -130,6 +178,22 @@ impl Gilrs {
         }
     }
 
+    /// Sets how often power supply state of connected gamepads is refreshed in the background.
+    /// `PowerChanged` event is emitted when it changes and [`Gamepad::power_info()`] returns the
+    /// cached value.
+    ///
+    /// Defaults to 5 seconds. Currently only used on Windows with XInput, other platforms read
+    /// power information when it's requested and don't emit `PowerChanged`.
+    pub fn set_power_info_interval(&mut self, interval: Duration) {
+        self.inner.set_power_info_interval(interval)
+    }
+
+    /// Returns how often power supply state is refreshed in the background or `None` if platform
+    /// doesn't refresh it.
+    pub fn power_info_interval(&self) -> Option<Duration> {
+        self.inner.power_info_interval()
+    }
+
     /// Returns id greater than id of last connected gamepad. The returned value is only hint
     /// and may be much larger than number of observed gamepads. For example, it may return maximum
     /// number of connected gamepads on platforms when this limit is small.
@@ -139,6 +203,12 @@ impl Gilrs {
     pub fn last_gamepad_hint(&self) -> usize {
         self.inner.last_gamepad_hint()
     }
+
+    /// Returns false if backend can't receive events anymore, for example because its event
+    /// thread panicked. Gamepads keep their last known state and no new events are returned.
+    pub fn is_alive(&self) -> bool {
+        self.inner.is_alive()
+    }
 }
 
 /// Provides information about gamepad.
@@ -164,6 +234,12 @@ impl Gamepad {
     /// Returned UUID should be the same as SLD2 uses. If platform does not provide any method to
     /// distinguish between gamepad models, nil UUID is returned.
     ///
+    /// The layout is the same as SDL2 joystick GUID (and `gamecontrollerdb.txt`): eight little
+    /// endian 16-bit words containing bus type, 0, vendor ID, 0, product ID, 0, version and 0. For
+    /// example Xbox 360 controller connected over USB has UUID
+    /// `030000005e0400008e02000014010000`. xinput and Windows Gaming Input gamepads always use nil
+    /// UUID, which is what SDL2 mappings call `xinput`.
+    ///
     /// It is recommended to process with the [UUID crate](https://crates.io/crates/uuid).
     /// Use `Uuid::from_bytes` method to create a `Uuid` from the returned bytes.
     pub fn uuid(&self) -> [u8; 16] {
@@ -186,6 +262,24 @@ impl Gamepad {
         self.inner.product_id()
     }
 
+    /// Returns the product version (hardware or firmware revision), when available.
+    ///
+    /// On Windows Gaming Input it's read from raw input device with the same vendor and product ID.
+    /// XInput backend can't read it.
+    pub fn version(&self) -> Option<u16> {
+        self.inner.version()
+    }
+
+    /// Lights player indicator LED matching `index` (0 for first player), or turns all of them
+    /// off if `index` is `None`.
+    ///
+    /// Returns error with `ErrorKind::Unsupported` if LEDs can't be controlled. Only Linux
+    /// supports it, for controllers handled by xpad driver, which have 4 player LEDs. Writing to
+    /// LED usually requires udev rule that gives user access to it.
+    pub fn set_player_index(&self, index: Option<u8>) -> io::Result<()> {
+        self.inner.set_player_index(index)
+    }
+
     /// Returns device's power supply state.
     pub fn power_info(&self) -> PowerInfo {
         self.inner.power_info()
@@ -196,6 +290,19 @@ impl Gamepad {
         self.inner.is_ff_supported()
     }
 
+    /// Returns features of the device. This asks the platform, so it's better to cache the result.
+    pub fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            has_rumble: self.is_ff_supported(),
+            num_buttons: self.buttons().len(),
+            num_axes: self.axes().len(),
+            subtype: self.inner.subtype(),
+            has_trigger_rumble: self.inner.has_trigger_rumble(),
+            // None of the backends can report these yet.
+            ..Default::default()
+        }
+    }
+
     /// Creates `FfDevice` corresponding to this gamepad.
     pub fn ff_device(&self) -> Option<FfDevice> {
         self.inner.ff_device().map(|inner| FfDevice { inner })
@@ -328,6 +435,14 @@ pub mod native_ev_codes {
     pub const AXIS_RT2: EvCode = EvCode(nec::AXIS_RT2);
     pub const AXIS_LT2: EvCode = EvCode(nec::AXIS_LT2);
 
+    /// Steering wheel and pedals of racing wheels. Only reported on Linux and macOS.
+    pub const AXIS_STEERING: EvCode = EvCode(nec::AXIS_STEERING);
+    pub const AXIS_THROTTLE: EvCode = EvCode(nec::AXIS_THROTTLE);
+    pub const AXIS_BRAKE: EvCode = EvCode(nec::AXIS_BRAKE);
+    /// Clutch pedal of racing wheels. Only reported on macOS, Linux doesn't have event code for
+    /// it.
+    pub const AXIS_CLUTCH: EvCode = EvCode(nec::AXIS_CLUTCH);
+
     pub const BTN_SOUTH: EvCode = EvCode(nec::BTN_SOUTH);
     pub const BTN_EAST: EvCode = EvCode(nec::BTN_EAST);
     pub const BTN_C: EvCode = EvCode(nec::BTN_C);
@@ -348,4 +463,7 @@ pub mod native_ev_codes {
     pub const BTN_DPAD_DOWN: EvCode = EvCode(nec::BTN_DPAD_DOWN);
     pub const BTN_DPAD_LEFT: EvCode = EvCode(nec::BTN_DPAD_LEFT);
     pub const BTN_DPAD_RIGHT: EvCode = EvCode(nec::BTN_DPAD_RIGHT);
+
+    /// Share/capture button of Xbox Series X|S controllers. Only reported on Linux.
+    pub const BTN_SHARE: EvCode = EvCode(nec::BTN_SHARE);
 }
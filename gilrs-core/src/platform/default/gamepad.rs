@@ -7,10 +7,11 @@
 #![allow(unused_variables)]
 
 use super::FfDevice;
-use crate::{AxisInfo, Event, PlatformError, PowerInfo};
+use crate::{AxisInfo, DeviceSubtype, Event, PlatformError, PowerInfo};
 use uuid::Uuid;
 
 use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::io;
 use std::time::Duration;
 
 #[derive(Debug)]
@@ -37,6 +38,16 @@ impl Gilrs {
     pub fn last_gamepad_hint(&self) -> usize {
         0
     }
+
+    pub fn is_alive(&self) -> bool {
+        true
+    }
+
+    pub(crate) fn set_power_info_interval(&mut self, _interval: Duration) {}
+
+    pub(crate) fn power_info_interval(&self) -> Option<Duration> {
+        None
+    }
 }
 
 #[derive(Debug)]
@@ -61,6 +72,14 @@ impl Gamepad {
         None
     }
 
+    pub fn version(&self) -> Option<u16> {
+        None
+    }
+
+    pub fn set_player_index(&self, _index: Option<u8>) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
     pub fn power_info(&self) -> PowerInfo {
         PowerInfo::Unknown
     }
@@ -69,6 +88,14 @@ impl Gamepad {
         false
     }
 
+    pub fn subtype(&self) -> DeviceSubtype {
+        DeviceSubtype::Unknown
+    }
+
+    pub fn has_trigger_rumble(&self) -> bool {
+        false
+    }
+
     /// Creates Ffdevice corresponding to this gamepad.
     pub fn ff_device(&self) -> Option<FfDevice> {
         Some(FfDevice)
@@ -146,4 +173,11 @@ pub mod native_ev_codes {
     pub const BTN_DPAD_DOWN: EvCode = EvCode(28);
     pub const BTN_DPAD_LEFT: EvCode = EvCode(29);
     pub const BTN_DPAD_RIGHT: EvCode = EvCode(30);
+
+    // Not reported by this backend.
+    pub const BTN_SHARE: EvCode = EvCode(31);
+    pub const AXIS_STEERING: EvCode = EvCode(32);
+    pub const AXIS_THROTTLE: EvCode = EvCode(33);
+    pub const AXIS_BRAKE: EvCode = EvCode(34);
+    pub const AXIS_CLUTCH: EvCode = EvCode(35);
 }
@@ -10,7 +10,7 @@ use super::ioctl;
 use super::ioctl::{input_absinfo, input_event};
 use super::udev::*;
 use crate::utils;
-use crate::{AxisInfo, Event, EventType};
+use crate::{AxisInfo, DeviceSubtype, DisconnectReason, Event, EventType};
 use crate::{PlatformError, PowerInfo};
 
 use libc as c;
@@ -26,7 +26,8 @@ use std::error;
 use std::ffi::OsStr;
 use std::ffi::{CStr, CString};
 use std::fmt::{Display, Formatter, Result as FmtResult};
-use std::fs::File;
+use std::fs::{self, File};
+use std::io;
 use std::mem::{self, MaybeUninit};
 use std::ops::Index;
 use std::os::raw::c_char;
@@ -294,6 +295,16 @@ impl Gilrs {
         self.gamepads.len()
     }
 
+    pub fn is_alive(&self) -> bool {
+        true
+    }
+
+    pub(crate) fn set_power_info_interval(&mut self, _interval: Duration) {}
+
+    pub(crate) fn power_info_interval(&self) -> Option<Duration> {
+        None
+    }
+
     fn handle_hotplug(&mut self) -> Option<Event> {
         while let Ok(event) = self.hotplug_rx.try_recv() {
             match event {
@@ -342,7 +353,10 @@ impl Gilrs {
                         }
 
                         self.gamepads[id].disconnect();
-                        return Some(Event::new(id, EventType::Disconnected));
+                        return Some(Event::new(
+                            id,
+                            EventType::Disconnected(DisconnectReason::Removed),
+                        ));
                     } else {
                         debug!("Could not find disconnected gamepad {devpath:?}");
                     }
@@ -547,8 +561,10 @@ pub struct Gamepad {
     uuid: Uuid,
     vendor_id: u16,
     product_id: u16,
+    version: u16,
     bt_capacity_fd: RawFd,
     bt_status_fd: RawFd,
+    player_led: Option<PathBuf>,
     axes_values: VecMap<i32>,
     buttons_values: VecMap<bool>,
     events: Vec<input_event>,
@@ -606,8 +622,10 @@ impl Gamepad {
             uuid: create_uuid(input_id),
             vendor_id: input_id.vendor,
             product_id: input_id.product,
+            version: input_id.version,
             bt_capacity_fd: cap,
             bt_status_fd: status,
+            player_led: Self::player_led(syspath),
             axes_values: VecMap::new(),
             buttons_values: VecMap::new(),
             events: Vec::new(),
@@ -784,6 +802,14 @@ impl Gamepad {
         (-1, -1)
     }
 
+    /// Returns path to brightness of player LED created by xpad driver.
+    fn player_led(syspath: &Path) -> Option<PathBuf> {
+        let leds = fs::read_dir(syspath.join("device/device/leds")).ok()?;
+        leds.filter_map(Result::ok)
+            .find(|entry| entry.file_name().to_string_lossy().starts_with("xpad"))
+            .map(|entry| entry.path().join("brightness"))
+    }
+
     fn event(&mut self) -> Option<(EventType, SystemTime)> {
         let mut skip = false;
         // Skip all unknown events and return Option on first know event or when there is no more
@@ -988,6 +1014,14 @@ impl Gamepad {
         self.ff_supported
     }
 
+    pub fn subtype(&self) -> DeviceSubtype {
+        DeviceSubtype::Unknown
+    }
+
+    pub fn has_trigger_rumble(&self) -> bool {
+        false
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -1004,6 +1038,22 @@ impl Gamepad {
         Some(self.product_id)
     }
 
+    pub fn version(&self) -> Option<u16> {
+        Some(self.version)
+    }
+
+    pub fn set_player_index(&self, index: Option<u8>) -> io::Result<()> {
+        let path = self.player_led.as_ref().ok_or(io::ErrorKind::Unsupported)?;
+        // Values 6-9 turn on LED of player 1-4, 0 turns all LEDs off.
+        let value = match index {
+            Some(index) if index < 4 => 6 + index,
+            Some(_) => return Err(io::ErrorKind::Unsupported.into()),
+            None => 0,
+        };
+
+        fs::write(path, value.to_string())
+    }
+
     pub fn ff_device(&self) -> Option<FfDevice> {
         if self.is_ff_supported() {
             FfDevice::new(&self.devpath).ok()
@@ -1056,25 +1106,7 @@ impl PartialEq for Gamepad {
 }
 
 fn create_uuid(iid: ioctl::input_id) -> Uuid {
-    let bus = (u32::from(iid.bustype)).to_be();
-    let vendor = iid.vendor.to_be();
-    let product = iid.product.to_be();
-    let version = iid.version.to_be();
-    Uuid::from_fields(
-        bus,
-        vendor,
-        0,
-        &[
-            (product >> 8) as u8,
-            product as u8,
-            0,
-            0,
-            (version >> 8) as u8,
-            version as u8,
-            0,
-            0,
-        ],
-    )
+    utils::sdl_uuid(iid.bustype, iid.vendor, iid.product, iid.version)
 }
 
 unsafe fn cstr_new(bytes: &[u8]) -> &CStr {
@@ -1191,6 +1223,9 @@ const BTN_DPAD_DOWN: u16 = 0x221;
 const BTN_DPAD_LEFT: u16 = 0x222;
 const BTN_DPAD_RIGHT: u16 = 0x223;
 
+// Used by xpad for share button of Xbox Series X|S controllers.
+const KEY_RECORD: u16 = 0xa7;
+
 const ABS_X: u16 = 0x00;
 const ABS_Y: u16 = 0x01;
 const ABS_Z: u16 = 0x02;
@@ -1203,6 +1238,11 @@ const ABS_HAT1X: u16 = 0x12;
 const ABS_HAT1Y: u16 = 0x13;
 const ABS_HAT2X: u16 = 0x14;
 const ABS_HAT2Y: u16 = 0x15;
+const ABS_WHEEL: u16 = 0x08;
+const ABS_GAS: u16 = 0x09;
+const ABS_BRAKE: u16 = 0x0a;
+// There is no event code for clutch, kernel only reports codes smaller than this.
+const ABS_CNT: u16 = 0x40;
 
 const FF_MAX: u16 = FF_GAIN;
 const FF_SQUARE: u16 = 0x58;
@@ -1289,6 +1329,10 @@ pub mod native_ev_codes {
         kind: EV_KEY,
         code: super::BTN_DPAD_RIGHT,
     };
+    pub const BTN_SHARE: EvCode = EvCode {
+        kind: EV_KEY,
+        code: super::KEY_RECORD,
+    };
 
     pub const AXIS_LSTICKX: EvCode = EvCode {
         kind: EV_ABS,
@@ -1338,6 +1382,23 @@ pub mod native_ev_codes {
         kind: EV_ABS,
         code: super::ABS_HAT2Y,
     };
+    pub const AXIS_STEERING: EvCode = EvCode {
+        kind: EV_ABS,
+        code: super::ABS_WHEEL,
+    };
+    pub const AXIS_THROTTLE: EvCode = EvCode {
+        kind: EV_ABS,
+        code: super::ABS_GAS,
+    };
+    pub const AXIS_BRAKE: EvCode = EvCode {
+        kind: EV_ABS,
+        code: super::ABS_BRAKE,
+    };
+    // Never reported.
+    pub const AXIS_CLUTCH: EvCode = EvCode {
+        kind: EV_ABS,
+        code: super::ABS_CNT,
+    };
 }
 
 #[cfg(test)]
@@ -7,7 +7,10 @@
 
 use super::io_kit::*;
 use super::FfDevice;
-use crate::{AxisInfo, Event, EventType, PlatformError, PowerInfo};
+use crate::utils::EventReceiver;
+use crate::{
+    utils, AxisInfo, DeviceSubtype, DisconnectReason, Event, EventType, PlatformError, PowerInfo,
+};
 use uuid::Uuid;
 
 use core_foundation::runloop::{kCFRunLoopDefaultMode, CFRunLoop};
@@ -20,8 +23,9 @@ use io_kit_sys::ret::IOReturn;
 use vec_map::VecMap;
 
 use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::io;
 use std::os::raw::c_void;
-use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::mpsc::{self, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
@@ -30,7 +34,7 @@ use std::time::Duration;
 pub struct Gilrs {
     gamepads: Vec<Gamepad>,
     device_infos: Arc<Mutex<Vec<DeviceInfo>>>,
-    rx: Receiver<(Event, Option<IOHIDDevice>)>,
+    rx: EventReceiver<(Event, Option<IOHIDDevice>)>,
 }
 
 impl Gilrs {
@@ -44,7 +48,7 @@ impl Gilrs {
         Ok(Gilrs {
             gamepads,
             device_infos,
-            rx,
+            rx: EventReceiver::new(rx),
         })
     }
 
@@ -82,17 +86,12 @@ impl Gilrs {
     }
 
     pub(crate) fn next_event(&mut self) -> Option<Event> {
-        let event = self.rx.try_recv().ok();
+        let event = self.rx.try_recv();
         self.handle_event(event)
     }
 
     pub(crate) fn next_event_blocking(&mut self, timeout: Option<Duration>) -> Option<Event> {
-        let event = if let Some(timeout) = timeout {
-            self.rx.recv_timeout(timeout).ok()
-        } else {
-            self.rx.recv().ok()
-        };
-
+        let event = self.rx.recv(timeout);
         self.handle_event(event)
     }
 
@@ -117,7 +116,7 @@ impl Gilrs {
                 Some(event)
             }
             Some((event, None)) => {
-                if event.event == EventType::Disconnected {
+                if let EventType::Disconnected(_) = event.event {
                     match self.gamepads.get_mut(event.id) {
                         Some(gamepad) => {
                             match self.device_infos.lock().unwrap().get_mut(event.id) {
@@ -149,6 +148,16 @@ impl Gilrs {
     pub fn last_gamepad_hint(&self) -> usize {
         self.gamepads.len()
     }
+
+    pub fn is_alive(&self) -> bool {
+        self.rx.is_alive()
+    }
+
+    pub(crate) fn set_power_info_interval(&mut self, _interval: Duration) {}
+
+    pub(crate) fn power_info_interval(&self) -> Option<Duration> {
+        None
+    }
 }
 
 #[derive(Debug)]
@@ -157,6 +166,7 @@ pub struct Gamepad {
     name: String,
     vendor: Option<u16>,
     product: Option<u16>,
+    version: Option<u16>,
     uuid: Uuid,
     entry_id: u64,
     location_id: u32,
@@ -242,6 +252,7 @@ impl Gamepad {
             name,
             vendor: device.get_vendor_id(),
             product: device.get_product_id(),
+            version: device.get_version(),
             uuid,
             entry_id,
             location_id,
@@ -259,11 +270,8 @@ impl Gamepad {
     }
 
     fn create_uuid(device: &IOHIDDevice) -> Option<Uuid> {
-        // SDL always uses USB bus for UUID
-        let bustype = u32::to_be(0x03);
-
         let vendor_id = match device.get_vendor_id() {
-            Some(vendor_id) => vendor_id.to_be(),
+            Some(vendor_id) => vendor_id,
             None => {
                 warn!("Failed to get vendor id of device");
                 0
@@ -271,7 +279,7 @@ impl Gamepad {
         };
 
         let product_id = match device.get_product_id() {
-            Some(product_id) => product_id.to_be(),
+            Some(product_id) => product_id,
             None => {
                 warn!("Failed to get product id of device");
                 0
@@ -279,7 +287,7 @@ impl Gamepad {
         };
 
         let version = match device.get_version() {
-            Some(version) => version.to_be(),
+            Some(version) => version,
             None => {
                 warn!("Failed to get version of device");
                 0
@@ -289,20 +297,12 @@ impl Gamepad {
         if vendor_id == 0 && product_id == 0 && version == 0 {
             None
         } else {
-            Some(Uuid::from_fields(
-                bustype,
+            // SDL always uses USB bus for UUID
+            Some(utils::sdl_uuid(
+                utils::SDL_HARDWARE_BUS_USB,
                 vendor_id,
-                0,
-                &[
-                    (product_id >> 8) as u8,
-                    product_id as u8,
-                    0,
-                    0,
-                    (version >> 8) as u8,
-                    version as u8,
-                    0,
-                    0,
-                ],
+                product_id,
+                version,
             ))
         }
     }
@@ -319,6 +319,14 @@ impl Gamepad {
         self.product
     }
 
+    pub fn version(&self) -> Option<u16> {
+        self.version
+    }
+
+    pub fn set_player_index(&self, _index: Option<u8>) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
     pub fn uuid(&self) -> Uuid {
         self.uuid
     }
@@ -331,6 +339,14 @@ impl Gamepad {
         false
     }
 
+    pub fn subtype(&self) -> DeviceSubtype {
+        DeviceSubtype::Unknown
+    }
+
+    pub fn has_trigger_rumble(&self) -> bool {
+        false
+    }
+
     pub fn mount_point(&self) -> Option<String> {
         let location_id = self.location_id.to_string();
         Some(location_id)
@@ -532,6 +548,22 @@ pub mod native_ev_codes {
         page: super::PAGE_GENERIC_DESKTOP,
         usage: super::USAGE_AXIS_LT2,
     };
+    pub const AXIS_STEERING: EvCode = EvCode {
+        page: super::PAGE_SIMULATION,
+        usage: super::USAGE_AXIS_STEERING,
+    };
+    pub const AXIS_THROTTLE: EvCode = EvCode {
+        page: super::PAGE_SIMULATION,
+        usage: super::USAGE_AXIS_THROTTLE,
+    };
+    pub const AXIS_BRAKE: EvCode = EvCode {
+        page: super::PAGE_SIMULATION,
+        usage: super::USAGE_AXIS_BRAKE,
+    };
+    pub const AXIS_CLUTCH: EvCode = EvCode {
+        page: super::PAGE_SIMULATION,
+        usage: super::USAGE_AXIS_CLUTCH,
+    };
 
     pub const BTN_SOUTH: EvCode = EvCode {
         page: super::PAGE_BUTTON,
@@ -610,6 +642,12 @@ pub mod native_ev_codes {
         page: super::PAGE_BUTTON,
         usage: super::USAGE_BTN_DPAD_RIGHT,
     };
+
+    // Not reported by this backend.
+    pub const BTN_SHARE: EvCode = EvCode {
+        page: super::PAGE_BUTTON,
+        usage: u32::MAX,
+    };
 }
 
 extern "C" fn device_matching_cb(
@@ -715,7 +753,10 @@ extern "C" fn device_removal_cb(
         }
     };
 
-    let _ = tx.send((Event::new(id, EventType::Disconnected), None));
+    let _ = tx.send((
+        Event::new(id, EventType::Disconnected(DisconnectReason::Removed)),
+        None,
+    ));
 }
 
 extern "C" fn input_value_cb(
@@ -347,10 +347,12 @@ impl IOHIDElement {
                     _ => false,
                 },
                 kHIDPage_Simulation => match usage {
-                    kHIDUsage_Sim_Rudder
+                    kHIDUsage_Sim_Steering
+                    | kHIDUsage_Sim_Rudder
                     | kHIDUsage_Sim_Throttle
                     | kHIDUsage_Sim_Accelerator
-                    | kHIDUsage_Sim_Brake => true,
+                    | kHIDUsage_Sim_Brake
+                    | kHIDUsage_Sim_Clutch => true,
                     _ => false,
                 },
                 _ => false,
@@ -597,6 +599,7 @@ extern "C" fn cf_set_applier(value: *const c_void, context: *const c_void) {
 // Usage Pages
 pub const PAGE_GENERIC_DESKTOP: u32 = kHIDPage_GenericDesktop;
 pub const PAGE_BUTTON: u32 = kHIDPage_Button;
+pub const PAGE_SIMULATION: u32 = kHIDPage_Simulation;
 
 // GenericDesktop Page (0x01)
 pub const USAGE_AXIS_LSTICKX: u32 = kHIDUsage_GD_X;
@@ -616,6 +619,12 @@ pub const USAGE_AXIS_LT: u32 = 0;
 pub const USAGE_AXIS_RT2: u32 = kHIDUsage_GD_Z;
 pub const USAGE_AXIS_LT2: u32 = kHIDUsage_GD_Rz;
 
+// Simulation Page (0x02)
+pub const USAGE_AXIS_STEERING: u32 = kHIDUsage_Sim_Steering;
+pub const USAGE_AXIS_THROTTLE: u32 = kHIDUsage_Sim_Accelerator;
+pub const USAGE_AXIS_BRAKE: u32 = kHIDUsage_Sim_Brake;
+pub const USAGE_AXIS_CLUTCH: u32 = kHIDUsage_Sim_Clutch;
+
 // Button Page (0x09)
 pub const USAGE_BTN_SOUTH: u32 = kHIDUsage_Button_1;
 pub const USAGE_BTN_EAST: u32 = kHIDUsage_Button_1 + 1;
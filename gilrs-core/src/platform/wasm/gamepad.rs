@@ -7,6 +7,7 @@
 
 use std::collections::VecDeque;
 use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::io;
 use std::time::Duration;
 
 use js_sys::RegExp;
@@ -16,7 +17,9 @@ use web_sys::{DomException, Gamepad as WebGamepad, GamepadButton, GamepadMapping
 
 use super::FfDevice;
 use crate::platform::native_ev_codes::{BTN_LT2, BTN_RT2};
-use crate::{AxisInfo, Event, EventType, PlatformError, PowerInfo};
+use crate::{
+    AxisInfo, DeviceSubtype, DisconnectReason, Event, EventType, PlatformError, PowerInfo,
+};
 #[cfg(feature = "serde-serialize")]
 use serde::{Deserialize, Serialize};
 
@@ -146,8 +149,10 @@ impl Gilrs {
                 (Some(old), None) => {
                     // Create a disconnect event
                     if old.connected {
-                        self.event_cache
-                            .push_back(Event::new(index, EventType::Disconnected));
+                        self.event_cache.push_back(Event::new(
+                            index,
+                            EventType::Disconnected(DisconnectReason::Removed),
+                        ));
                         old.connected = false;
                     }
                 }
@@ -175,6 +180,16 @@ impl Gilrs {
     pub fn last_gamepad_hint(&self) -> usize {
         self.gamepads.len()
     }
+
+    pub fn is_alive(&self) -> bool {
+        true
+    }
+
+    pub(crate) fn set_power_info_interval(&mut self, _interval: Duration) {}
+
+    pub(crate) fn power_info_interval(&self) -> Option<Duration> {
+        None
+    }
 }
 
 #[derive(Debug)]
@@ -320,6 +335,14 @@ impl Gamepad {
         self.product
     }
 
+    pub fn version(&self) -> Option<u16> {
+        None
+    }
+
+    pub fn set_player_index(&self, _index: Option<u8>) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
     pub fn is_connected(&self) -> bool {
         self.gamepad.connected()
     }
@@ -332,6 +355,14 @@ impl Gamepad {
         false
     }
 
+    pub fn subtype(&self) -> DeviceSubtype {
+        DeviceSubtype::Unknown
+    }
+
+    pub fn has_trigger_rumble(&self) -> bool {
+        false
+    }
+
     pub fn ff_device(&self) -> Option<FfDevice> {
         None
     }
@@ -442,6 +473,13 @@ pub mod native_ev_codes {
     pub const BTN_DPAD_LEFT: EvCode = EvCode(29);
     pub const BTN_DPAD_RIGHT: EvCode = EvCode(30);
 
+    // Not reported by this backend.
+    pub const BTN_SHARE: EvCode = EvCode(31);
+    pub const AXIS_STEERING: EvCode = EvCode(32);
+    pub const AXIS_THROTTLE: EvCode = EvCode(33);
+    pub const AXIS_BRAKE: EvCode = EvCode(34);
+    pub const AXIS_CLUTCH: EvCode = EvCode(35);
+
     pub(super) static BUTTONS: [EvCode; 17] = [
         BTN_SOUTH,
         BTN_EAST,
@@ -7,11 +7,16 @@
 
 use super::FfDevice;
 use crate::native_ev_codes as nec;
-use crate::{utils, AxisInfo, Event, EventType, PlatformError, PowerInfo};
+use crate::utils::EventReceiver;
+use crate::{
+    utils, AxisInfo, DeviceSubtype, DisconnectReason, Event, EventType, PlatformError, PowerInfo,
+};
 
 #[cfg(feature = "serde-serialize")]
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::io;
+use std::mem;
 use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
 use std::thread::JoinHandle;
 use std::time::{Duration, SystemTime};
@@ -25,15 +30,25 @@ use windows::Gaming::Input::{
     RawGameController,
 };
 use windows::System::Power::BatteryStatus;
+use windows::Win32::UI::Input::{
+    GetRawInputDeviceInfoW, GetRawInputDeviceList, RAWINPUTDEVICELIST, RIDI_DEVICEINFO,
+    RID_DEVICE_INFO, RIM_TYPEHID,
+};
 
-const SDL_HARDWARE_BUS_USB: u32 = 0x03;
-// const SDL_HARDWARE_BUS_BLUETOOTH: u32 = 0x05;
+// const SDL_HARDWARE_BUS_BLUETOOTH: u16 = 0x05;
 
 // The general consensus is that standard xbox controllers poll at ~125 hz which
 // means 8 ms between updates.
 // Seems like a good target for how often we update the background thread.
 const EVENT_THREAD_SLEEP_TIME: u64 = 8;
 
+const MICROSOFT_VENDOR_ID: u16 = 0x045e;
+// Xbox One, Xbox One S, Elite, Elite Series 2 and Xbox Series X|S controllers (USB and Bluetooth).
+// Xbox 360 controllers don't have trigger motors.
+const TRIGGER_RUMBLE_PRODUCT_IDS: [u16; 12] = [
+    0x02d1, 0x02dd, 0x02e0, 0x02e3, 0x02ea, 0x02fd, 0x0b00, 0x0b05, 0x0b12, 0x0b13, 0x0b20, 0x0b22,
+];
+
 const WGI_TO_GILRS_BUTTON_MAP: [(GamepadButtons, crate::EvCode); 14] = [
     (GamepadButtons::DPadUp, nec::BTN_DPAD_UP),
     (GamepadButtons::DPadDown, nec::BTN_DPAD_DOWN),
@@ -74,7 +89,7 @@ impl WgiEvent {
 #[derive(Debug)]
 pub struct Gilrs {
     gamepads: Vec<Gamepad>,
-    rx: Receiver<WgiEvent>,
+    rx: EventReceiver<WgiEvent>,
     join_handle: Option<JoinHandle<()>>,
     stop_tx: Sender<()>,
 }
@@ -103,7 +118,7 @@ impl Gilrs {
         let join_handle = Some(Self::spawn_thread(tx, stop_rx));
         Ok(Gilrs {
             gamepads,
-            rx,
+            rx: EventReceiver::new(rx),
             join_handle,
             stop_tx,
         })
@@ -127,7 +142,10 @@ impl Gilrs {
             EventHandler::new(move |_, g: &Option<RawGameController>| {
                 if let Some(g) = g {
                     removed_tx
-                        .send(WgiEvent::new(g.clone(), EventType::Disconnected))
+                        .send(WgiEvent::new(
+                            g.clone(),
+                            EventType::Disconnected(DisconnectReason::Removed),
+                        ))
                         .expect("should be able to send to main thread");
                 }
                 Ok(())
@@ -209,22 +227,13 @@ impl Gilrs {
     pub(crate) fn next_event(&mut self) -> Option<Event> {
         self.rx
             .try_recv()
-            .ok()
             .map(|wgi_event: WgiEvent| self.handle_event(wgi_event))
     }
 
     pub(crate) fn next_event_blocking(&mut self, timeout: Option<Duration>) -> Option<Event> {
-        if let Some(timeout) = timeout {
-            self.rx
-                .recv_timeout(timeout)
-                .ok()
-                .map(|wgi_event: WgiEvent| self.handle_event(wgi_event))
-        } else {
-            self.rx
-                .recv()
-                .ok()
-                .map(|wgi_event: WgiEvent| self.handle_event(wgi_event))
-        }
+        self.rx
+            .recv(timeout)
+            .map(|wgi_event: WgiEvent| self.handle_event(wgi_event))
     }
 
     fn handle_event(&mut self, wgi_event: WgiEvent) -> Event {
@@ -248,7 +257,7 @@ impl Gilrs {
 
         match wgi_event.event {
             EventType::Connected => self.gamepads[id].is_connected = true,
-            EventType::Disconnected => self.gamepads[id].is_connected = false,
+            EventType::Disconnected(_) => self.gamepads[id].is_connected = false,
             _ => (),
         }
         Event {
@@ -265,6 +274,16 @@ impl Gilrs {
     pub fn last_gamepad_hint(&self) -> usize {
         self.gamepads.len()
     }
+
+    pub fn is_alive(&self) -> bool {
+        self.rx.is_alive()
+    }
+
+    pub(crate) fn set_power_info_interval(&mut self, _interval: Duration) {}
+
+    pub(crate) fn power_info_interval(&self) -> Option<Duration> {
+        None
+    }
 }
 
 impl Drop for Gilrs {
@@ -332,6 +351,60 @@ fn direction_from_switch(switch: GameControllerSwitchPosition) -> (i32, i32) {
     }
 }
 
+/// Returns version of connected HID device with given vendor and product ID.
+///
+/// `RawGameController` doesn't expose version, so it's read from raw input device with the same
+/// IDs. Returns `None` if there are several such devices with different versions.
+fn hid_version(vendor_id: u16, product_id: u16) -> Option<u16> {
+    let entry_size = mem::size_of::<RAWINPUTDEVICELIST>() as u32;
+    let mut count = 0;
+    if unsafe { GetRawInputDeviceList(None, &mut count, entry_size) } == u32::MAX {
+        return None;
+    }
+
+    let mut devices = vec![RAWINPUTDEVICELIST::default(); count as usize];
+    let count =
+        unsafe { GetRawInputDeviceList(Some(devices.as_mut_ptr()), &mut count, entry_size) };
+    // Fails if device was connected since first call.
+    if count == u32::MAX {
+        return None;
+    }
+    devices.truncate(count as usize);
+
+    let mut version = None;
+    for device in devices.iter().filter(|device| device.dwType == RIM_TYPEHID) {
+        let mut info = RID_DEVICE_INFO {
+            cbSize: mem::size_of::<RID_DEVICE_INFO>() as u32,
+            ..Default::default()
+        };
+        let mut size = info.cbSize;
+        let res = unsafe {
+            GetRawInputDeviceInfoW(
+                device.hDevice,
+                RIDI_DEVICEINFO,
+                Some(&mut info as *mut RID_DEVICE_INFO as *mut _),
+                &mut size,
+            )
+        };
+        if res == 0 || res == u32::MAX || info.dwType != RIM_TYPEHID {
+            continue;
+        }
+
+        let hid = unsafe { info.Anonymous.hid };
+        if hid.dwVendorId != u32::from(vendor_id) || hid.dwProductId != u32::from(product_id) {
+            continue;
+        }
+
+        let hid_version = u16::try_from(hid.dwVersionNumber).ok()?;
+        match version {
+            Some(version) if version != hid_version => return None,
+            _ => version = Some(hid_version),
+        }
+    }
+
+    version
+}
+
 #[derive(Clone)]
 enum Reading {
     Raw(RawGamepadReading),
@@ -504,6 +577,7 @@ pub struct Gamepad {
     /// If the controller has a [Gamepad](https://learn.microsoft.com/en-us/uwp/api/windows.gaming.input.gamepad?view=winrt-22621)
     /// mapping, this is used to access the mapped values.
     wgi_gamepad: Option<WgiGamepad>,
+    version: Option<u16>,
     axes: Option<Vec<EvCode>>,
     buttons: Option<Vec<EvCode>>,
 }
@@ -524,35 +598,26 @@ impl Gamepad {
         let uuid = match wgi_gamepad.is_some() {
             true => Uuid::nil(),
             false => {
-                let vendor_id = raw_game_controller.HardwareVendorId().unwrap_or(0).to_be();
-                let product_id = raw_game_controller.HardwareProductId().unwrap_or(0).to_be();
-                let version = 0;
+                let vendor_id = raw_game_controller.HardwareVendorId().unwrap_or(0);
+                let product_id = raw_game_controller.HardwareProductId().unwrap_or(0);
 
                 // SDL uses the SDL_HARDWARE_BUS_BLUETOOTH bustype for IsWireless devices:
                 // https://github.com/libsdl-org/SDL/blob/294ccba0a23b37fffef62189423444f93732e565/src/joystick/windows/SDL_windows_gaming_input.c#L335-L338
                 // In my testing though, it caused my controllers to not find mappings.
                 // SDL only uses their WGI implementation for UWP apps so I guess it hasn't been
                 // used enough for people to submit mappings with the different bustype.
-                let bustype = SDL_HARDWARE_BUS_USB.to_be();
-
-                Uuid::from_fields(
-                    bustype,
-                    vendor_id,
-                    0,
-                    &[
-                        (product_id >> 8) as u8,
-                        product_id as u8,
-                        0,
-                        0,
-                        (version >> 8) as u8,
-                        version as u8,
-                        0,
-                        0,
-                    ],
-                )
+                utils::sdl_uuid(utils::SDL_HARDWARE_BUS_USB, vendor_id, product_id, 0)
             }
         };
 
+        let version = match (
+            raw_game_controller.HardwareVendorId(),
+            raw_game_controller.HardwareProductId(),
+        ) {
+            (Ok(vendor_id), Ok(product_id)) => hid_version(vendor_id, product_id),
+            _ => None,
+        };
+
         let mut gamepad = Gamepad {
             id,
             name,
@@ -561,6 +626,7 @@ impl Gamepad {
             raw_game_controller,
             non_roamable_id,
             wgi_gamepad,
+            version,
             axes: None,
             buttons: None,
         };
@@ -580,6 +646,10 @@ impl Gamepad {
         self.uuid
     }
 
+    pub fn mount_point(&self) -> Option<String> {
+        None
+    }
+
     pub fn vendor_id(&self) -> Option<u16> {
         self.raw_game_controller.HardwareVendorId().ok()
     }
@@ -588,6 +658,14 @@ impl Gamepad {
         self.raw_game_controller.HardwareProductId().ok()
     }
 
+    pub fn version(&self) -> Option<u16> {
+        self.version
+    }
+
+    pub fn set_player_index(&self, _index: Option<u8>) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
     pub fn is_connected(&self) -> bool {
         self.is_connected
     }
@@ -633,6 +711,24 @@ impl Gamepad {
                 .is_some()
     }
 
+    pub fn subtype(&self) -> DeviceSubtype {
+        if self.wgi_gamepad.is_some() {
+            DeviceSubtype::Gamepad
+        } else {
+            DeviceSubtype::Unknown
+        }
+    }
+
+    /// WGI doesn't say whether `GamepadVibration::LeftTrigger` and `RightTrigger` do anything, so
+    /// we only report trigger motors for Microsoft controllers known to have them.
+    pub fn has_trigger_rumble(&self) -> bool {
+        self.is_ff_supported()
+            && self.vendor_id() == Some(MICROSOFT_VENDOR_ID)
+            && self
+                .product_id()
+                .map_or(false, |id| TRIGGER_RUMBLE_PRODUCT_IDS.contains(&id))
+    }
+
     pub fn ff_device(&self) -> Option<FfDevice> {
         Some(FfDevice::new(self.id, self.wgi_gamepad.clone()))
     }
@@ -899,6 +995,30 @@ pub mod native_ev_codes {
         index: u32::MAX,
     };
 
+    // Windows.Gaming.Input doesn't expose share button, so this code is never reported.
+    pub const BTN_SHARE: EvCode = EvCode {
+        kind: EvCodeKind::Button,
+        index: u32::MAX - 4,
+    };
+
+    // Racing wheels are not read through `RacingWheel` class, so these codes are never reported.
+    pub const AXIS_STEERING: EvCode = EvCode {
+        kind: EvCodeKind::Axis,
+        index: u32::MAX - 3,
+    };
+    pub const AXIS_CLUTCH: EvCode = EvCode {
+        kind: EvCodeKind::Axis,
+        index: u32::MAX - 2,
+    };
+    pub const AXIS_THROTTLE: EvCode = EvCode {
+        kind: EvCodeKind::Axis,
+        index: u32::MAX - 1,
+    };
+    pub const AXIS_BRAKE: EvCode = EvCode {
+        kind: EvCodeKind::Axis,
+        index: u32::MAX,
+    };
+
     pub(super) static BUTTONS: [EvCode; 14] = [
         BTN_WEST,
         BTN_SOUTH,
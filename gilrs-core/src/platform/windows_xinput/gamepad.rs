@@ -5,16 +5,21 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
-use super::FfDevice;
-use crate::{AxisInfo, Event, EventType, PlatformError, PowerInfo};
+use super::{hotplug, FfDevice};
+use crate::utils::EventReceiver;
+use crate::{
+    AxisInfo, DeviceSubtype, DisconnectReason, Event, EventType, PlatformError, PowerInfo,
+};
 
 use std::error::Error as StdError;
 use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::io;
 use std::sync::{
-    mpsc::{self, Receiver, Sender},
+    atomic::{AtomicU64, Ordering},
+    mpsc::{self, Sender},
     Arc,
 };
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::{mem, thread, u16, u32};
 
 use rusty_xinput::{
@@ -32,13 +37,31 @@ use winapi::um::xinput::{
 // Chosen by dice roll ;)
 const EVENT_THREAD_SLEEP_TIME: u64 = 10;
 const ITERATIONS_TO_CHECK_IF_CONNECTED: u64 = 100;
+// XInputGetState() on empty slot can take about 1ms, so we check empty slots less often the longer
+// they stay empty.
+const MAX_ITERATIONS_TO_CHECK_IF_CONNECTED: u64 = 8 * ITERATIONS_TO_CHECK_IF_CONNECTED;
+// With device notifications, polling is only a fallback in case we miss one.
+const MAX_ITERATIONS_TO_CHECK_IF_CONNECTED_NOTIFIED: u64 = 60 * ITERATIONS_TO_CHECK_IF_CONNECTED;
+
+// Event thread that didn't run for this long was most likely suspended together with the system.
+// Slots can be renumbered after resume, so we check identity of connected devices.
+const RESUME_GAP: Duration = Duration::from_secs(1);
+
+// XInputGetState() can fail for a moment, for example while wireless controller is reconnecting.
+// Gamepad is reported as disconnected only after this many failed reads in a row.
+const MAX_CONSECUTIVE_ERRORS: u32 = 50;
+
+// Battery level changes slowly, there is no need to check it often.
+const DEFAULT_POWER_INFO_INTERVAL_MS: u64 = 5000;
 
 const MAX_XINPUT_CONTROLLERS: usize = 4;
 
 #[derive(Debug)]
 pub struct Gilrs {
     gamepads: [Gamepad; MAX_XINPUT_CONTROLLERS],
-    rx: Receiver<Event>,
+    rx: EventReceiver<Event>,
+    // Shared with event thread.
+    power_info_interval_ms: Arc<AtomicU64>,
 }
 
 impl Gilrs {
@@ -54,36 +77,55 @@ impl Gilrs {
         }
 
         // Map controller IDs to Gamepads
-        let gamepads = gamepad_ids.map(|id| Gamepad::new(id as u32, xinput_handle.clone()));
+        let mut gamepads = gamepad_ids.map(|id| Gamepad::new(id as u32, xinput_handle.clone()));
 
         let mut connected: [bool; MAX_XINPUT_CONTROLLERS] = Default::default();
+        let mut power = [PowerInfo::Unknown; MAX_XINPUT_CONTROLLERS];
 
-        // Iterate through each controller ID and set connected state
+        // Check every slot once. Event thread starts with the same state, so it will only report
+        // changes from it.
         for id in 0..MAX_XINPUT_CONTROLLERS {
-            connected[id] = gamepads[id].is_connected;
+            let event = if xinput_handle.get_state(id as u32).is_ok() {
+                EventType::Connected
+            } else {
+                EventType::Disconnected(DisconnectReason::Unknown)
+            };
+            gamepads[id].handle_event(&event);
+            connected[id] = gamepads[id].is_connected();
+
+            if connected[id] {
+                power[id] = battery_info(&xinput_handle, id as u32);
+                gamepads[id].handle_event(&EventType::PowerChanged(power[id]));
+            }
         }
 
         let (tx, rx) = mpsc::channel();
-        Self::spawn_thread(tx, connected, xinput_handle.clone());
+        let power_info_interval_ms = Arc::new(AtomicU64::new(DEFAULT_POWER_INFO_INTERVAL_MS));
+        Self::spawn_thread(
+            tx,
+            connected,
+            power,
+            power_info_interval_ms.clone(),
+            xinput_handle.clone(),
+        );
 
         // Coerce gamepads vector to slice
-        Ok(Gilrs { gamepads, rx })
+        Ok(Gilrs {
+            gamepads,
+            rx: EventReceiver::new(rx),
+            power_info_interval_ms,
+        })
     }
 
     pub(crate) fn next_event(&mut self) -> Option<Event> {
-        let ev = self.rx.try_recv().ok();
+        let ev = self.rx.try_recv();
         self.handle_evevnt(ev);
 
         ev
     }
 
     pub(crate) fn next_event_blocking(&mut self, timeout: Option<Duration>) -> Option<Event> {
-        let ev = if let Some(tiemout) = timeout {
-            self.rx.recv_timeout(tiemout).ok()
-        } else {
-            self.rx.recv().ok()
-        };
-
+        let ev = self.rx.recv(timeout);
         self.handle_evevnt(ev);
 
         ev
@@ -91,10 +133,8 @@ impl Gilrs {
 
     fn handle_evevnt(&mut self, ev: Option<Event>) {
         if let Some(ev) = ev {
-            match ev.event {
-                EventType::Connected => self.gamepads[ev.id].is_connected = true,
-                EventType::Disconnected => self.gamepads[ev.id].is_connected = false,
-                _ => (),
+            if let Some(gamepad) = self.gamepads.get_mut(ev.id) {
+                gamepad.handle_event(&ev.event);
             }
         }
     }
@@ -107,9 +147,26 @@ impl Gilrs {
         self.gamepads.len()
     }
 
+    pub fn is_alive(&self) -> bool {
+        self.rx.is_alive()
+    }
+
+    pub(crate) fn set_power_info_interval(&mut self, interval: Duration) {
+        self.power_info_interval_ms
+            .store(interval.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn power_info_interval(&self) -> Option<Duration> {
+        Some(Duration::from_millis(
+            self.power_info_interval_ms.load(Ordering::Relaxed),
+        ))
+    }
+
     fn spawn_thread(
         tx: Sender<Event>,
         connected: [bool; MAX_XINPUT_CONTROLLERS],
+        power: [PowerInfo; MAX_XINPUT_CONTROLLERS],
+        power_info_interval_ms: Arc<AtomicU64>,
         xinput_handle: Arc<XInputHandle>,
     ) {
         std::thread::Builder::new()
@@ -119,18 +176,85 @@ impl Gilrs {
                 let mut prev_states: [XState; MAX_XINPUT_CONTROLLERS] =
                     [mem::zeroed::<XState>(); MAX_XINPUT_CONTROLLERS];
                 let mut connected = connected;
+                let mut power = power;
+                let mut next_power_check = 0;
+                let notifications = hotplug::start();
+                let max_interval = if notifications {
+                    MAX_ITERATIONS_TO_CHECK_IF_CONNECTED_NOTIFIED
+                } else {
+                    MAX_ITERATIONS_TO_CHECK_IF_CONNECTED
+                };
+                let mut probes = [EmptySlotProbe::new(max_interval); MAX_XINPUT_CONTROLLERS];
+                // Events of current iteration, per slot.
+                let mut queues: [Vec<Event>; MAX_XINPUT_CONTROLLERS] = Default::default();
+                let mut device_changes = hotplug::changes();
                 let mut counter = 0;
+                let mut identities = [None; MAX_XINPUT_CONTROLLERS];
+                let mut errors = [0u32; MAX_XINPUT_CONTROLLERS];
+                for id in 0..MAX_XINPUT_CONTROLLERS {
+                    if connected[id] {
+                        identities[id] = DeviceIdentity::read(&xinput_handle, id as u32);
+                    }
+                }
+                let mut last_iteration = Instant::now();
 
                 loop {
+                    // Driver restart and resume from sleep can move devices to other slots
+                    // without slot being reported as empty.
+                    let mut check_identity = last_iteration.elapsed() > RESUME_GAP;
+                    last_iteration = Instant::now();
+
+                    if notifications {
+                        let changes = hotplug::changes();
+                        if changes != device_changes {
+                            device_changes = changes;
+                            check_identity = true;
+                            for probe in &mut probes {
+                                probe.wake(counter);
+                            }
+                        }
+                    }
+
                     for id in 0..MAX_XINPUT_CONTROLLERS {
-                        if *connected.get_unchecked(id)
-                            || counter % ITERATIONS_TO_CHECK_IF_CONNECTED == 0
-                        {
+                        if *connected.get_unchecked(id) || probes[id].is_due(counter) {
                             match xinput_handle.get_state(id as u32) {
                                 Ok(XInputState { raw: state }) => {
+                                    errors[id] = 0;
+                                    if connected[id] {
+                                        let identity = if check_identity {
+                                            DeviceIdentity::read(&xinput_handle, id as u32)
+                                        } else {
+                                            identities[id]
+                                        };
+                                        if is_renumbered(
+                                            prev_states[id].dwPacketNumber,
+                                            state.dwPacketNumber,
+                                            identities[id],
+                                            identity,
+                                        ) {
+                                            debug!("Gamepad in slot {} was replaced", id);
+                                            connected[id] = false;
+                                            power[id] = PowerInfo::Unknown;
+                                            prev_states[id] = mem::zeroed();
+                                            queues[id].push(Event::new(
+                                                id,
+                                                EventType::Disconnected(DisconnectReason::Replaced),
+                                            ));
+                                        }
+                                    }
+
                                     if !connected[id] {
                                         connected[id] = true;
-                                        let _ = tx.send(Event::new(id, EventType::Connected));
+                                        identities[id] =
+                                            DeviceIdentity::read(&xinput_handle, id as u32);
+                                        probes[id].reset(counter);
+                                        queues[id].push(Event::new(id, EventType::Connected));
+                                        Self::check_power(
+                                            id,
+                                            &mut power[id],
+                                            &xinput_handle,
+                                            &mut queues[id],
+                                        );
                                     }
 
                                     if state.dwPacketNumber != prev_states[id].dwPacketNumber {
@@ -138,21 +262,80 @@ impl Gilrs {
                                             id,
                                             &state.Gamepad,
                                             &prev_states[id].Gamepad,
-                                            &tx,
+                                            &mut queues[id],
                                         );
                                         prev_states[id] = state;
                                     }
                                 }
                                 Err(XInputUsageError::DeviceNotConnected) if connected[id] => {
+                                    errors[id] = 0;
                                     connected[id] = false;
-                                    let _ = tx.send(Event::new(id, EventType::Disconnected));
+                                    power[id] = PowerInfo::Unknown;
+                                    probes[id].reset(counter);
+                                    queues[id].push(Event::new(
+                                        id,
+                                        EventType::Disconnected(DisconnectReason::Removed),
+                                    ));
+                                }
+                                Err(XInputUsageError::DeviceNotConnected) => {
+                                    probes[id].failed(counter)
+                                }
+                                // Error can be transient, keep last state for a while.
+                                Err(e)
+                                    if connected[id] && errors[id] + 1 < MAX_CONSECUTIVE_ERRORS =>
+                                {
+                                    if errors[id] == 0 {
+                                        warn!("Failed to get gamepad state: {:?}", e);
+                                    }
+                                    errors[id] += 1;
+                                }
+                                // We can't read state of this gamepad anymore, report it as
+                                // disconnected. It will be connected again once XInputGetState()
+                                // succeeds.
+                                Err(e) if connected[id] => {
+                                    error!("Failed to get gamepad state: {:?}", e);
+                                    errors[id] = 0;
+                                    connected[id] = false;
+                                    power[id] = PowerInfo::Unknown;
+                                    probes[id].reset(counter);
+                                    let reason = match e {
+                                        XInputUsageError::UnknownError(code) => {
+                                            DisconnectReason::Error(code)
+                                        }
+                                        _ => DisconnectReason::Unknown,
+                                    };
+                                    queues[id]
+                                        .push(Event::new(id, EventType::Disconnected(reason)));
+                                }
+                                Err(e) => {
+                                    debug!("Failed to get gamepad state: {:?}", e);
+                                    probes[id].failed(counter);
                                 }
-                                Err(XInputUsageError::DeviceNotConnected) => (),
-                                Err(e) => error!("Failed to get gamepad state: {:?}", e),
                             }
                         }
                     }
 
+                    let interval = power_info_interval_ms.load(Ordering::Relaxed);
+                    if interval != 0 && counter >= next_power_check {
+                        next_power_check =
+                            counter.saturating_add((interval / EVENT_THREAD_SLEEP_TIME).max(1));
+                        for id in 0..MAX_XINPUT_CONTROLLERS {
+                            if connected[id] {
+                                Self::check_power(
+                                    id,
+                                    &mut power[id],
+                                    &xinput_handle,
+                                    &mut queues[id],
+                                );
+                            }
+                        }
+                    }
+
+                    let first = counter as usize % MAX_XINPUT_CONTROLLERS;
+                    Self::interleave(&mut queues, first, |event| {
+                        let _ = tx.send(event);
+                    });
+
                     counter = counter.wrapping_add(1);
                     thread::sleep(Duration::from_millis(EVENT_THREAD_SLEEP_TIME));
                 }
@@ -160,9 +343,42 @@ impl Gilrs {
             .expect("failed to spawn thread");
     }
 
-    fn compare_state(id: usize, g: &XGamepad, pg: &XGamepad, tx: &Sender<Event>) {
+    /// Reads battery information and queues `PowerChanged` if it's different from `power`.
+    fn check_power(
+        id: usize,
+        power: &mut PowerInfo,
+        xinput_handle: &XInputHandle,
+        out: &mut Vec<Event>,
+    ) {
+        let info = battery_info(xinput_handle, id as u32);
+        if info != *power {
+            *power = info;
+            out.push(Event::new(id, EventType::PowerChanged(info)));
+        }
+    }
+
+    /// Passes events of all slots to `send`, one event from each slot in turn, so a slot with a
+    /// lot of events can't delay other slots. Slot that goes first changes with `first`. Queues
+    /// are empty afterwards.
+    fn interleave(queues: &mut [Vec<Event>], first: usize, mut send: impl FnMut(Event)) {
+        let longest = queues.iter().map(Vec::len).max().unwrap_or(0);
+        for i in 0..longest {
+            for j in 0..queues.len() {
+                let slot = (first + j) % queues.len();
+                if let Some(&event) = queues[slot].get(i) {
+                    send(event);
+                }
+            }
+        }
+
+        for queue in queues {
+            queue.clear();
+        }
+    }
+
+    fn compare_state(id: usize, g: &XGamepad, pg: &XGamepad, out: &mut Vec<Event>) {
         if g.bLeftTrigger != pg.bLeftTrigger {
-            let _ = tx.send(Event::new(
+            out.push(Event::new(
                 id,
                 EventType::AxisValueChanged(
                     g.bLeftTrigger as i32,
@@ -171,7 +387,7 @@ impl Gilrs {
             ));
         }
         if g.bRightTrigger != pg.bRightTrigger {
-            let _ = tx.send(Event::new(
+            out.push(Event::new(
                 id,
                 EventType::AxisValueChanged(
                     g.bRightTrigger as i32,
@@ -180,7 +396,7 @@ impl Gilrs {
             ));
         }
         if g.sThumbLX != pg.sThumbLX {
-            let _ = tx.send(Event::new(
+            out.push(Event::new(
                 id,
                 EventType::AxisValueChanged(
                     g.sThumbLX as i32,
@@ -189,7 +405,7 @@ impl Gilrs {
             ));
         }
         if g.sThumbLY != pg.sThumbLY {
-            let _ = tx.send(Event::new(
+            out.push(Event::new(
                 id,
                 EventType::AxisValueChanged(
                     g.sThumbLY as i32,
@@ -198,7 +414,7 @@ impl Gilrs {
             ));
         }
         if g.sThumbRX != pg.sThumbRX {
-            let _ = tx.send(Event::new(
+            out.push(Event::new(
                 id,
                 EventType::AxisValueChanged(
                     g.sThumbRX as i32,
@@ -207,7 +423,7 @@ impl Gilrs {
             ));
         }
         if g.sThumbRY != pg.sThumbRY {
-            let _ = tx.send(Event::new(
+            out.push(Event::new(
                 id,
                 EventType::AxisValueChanged(
                     g.sThumbRY as i32,
@@ -216,168 +432,171 @@ impl Gilrs {
             ));
         }
         if !is_mask_eq(g.wButtons, pg.wButtons, XINPUT_GAMEPAD_DPAD_UP) {
-            let _ = match g.wButtons & XINPUT_GAMEPAD_DPAD_UP != 0 {
-                true => tx.send(Event::new(
+            match g.wButtons & XINPUT_GAMEPAD_DPAD_UP != 0 {
+                true => out.push(Event::new(
                     id,
                     EventType::ButtonPressed(crate::native_ev_codes::BTN_DPAD_UP),
                 )),
-                false => tx.send(Event::new(
+                false => out.push(Event::new(
                     id,
                     EventType::ButtonReleased(crate::native_ev_codes::BTN_DPAD_UP),
                 )),
             };
         }
         if !is_mask_eq(g.wButtons, pg.wButtons, XINPUT_GAMEPAD_DPAD_DOWN) {
-            let _ = match g.wButtons & XINPUT_GAMEPAD_DPAD_DOWN != 0 {
-                true => tx.send(Event::new(
+            match g.wButtons & XINPUT_GAMEPAD_DPAD_DOWN != 0 {
+                true => out.push(Event::new(
                     id,
                     EventType::ButtonPressed(crate::native_ev_codes::BTN_DPAD_DOWN),
                 )),
-                false => tx.send(Event::new(
+                false => out.push(Event::new(
                     id,
                     EventType::ButtonReleased(crate::native_ev_codes::BTN_DPAD_DOWN),
                 )),
             };
         }
         if !is_mask_eq(g.wButtons, pg.wButtons, XINPUT_GAMEPAD_DPAD_LEFT) {
-            let _ = match g.wButtons & XINPUT_GAMEPAD_DPAD_LEFT != 0 {
-                true => tx.send(Event::new(
+            match g.wButtons & XINPUT_GAMEPAD_DPAD_LEFT != 0 {
+                true => out.push(Event::new(
                     id,
                     EventType::ButtonPressed(crate::native_ev_codes::BTN_DPAD_LEFT),
                 )),
-                false => tx.send(Event::new(
+                false => out.push(Event::new(
                     id,
                     EventType::ButtonReleased(crate::native_ev_codes::BTN_DPAD_LEFT),
                 )),
             };
         }
         if !is_mask_eq(g.wButtons, pg.wButtons, XINPUT_GAMEPAD_DPAD_RIGHT) {
-            let _ = match g.wButtons & XINPUT_GAMEPAD_DPAD_RIGHT != 0 {
-                true => tx.send(Event::new(
+            match g.wButtons & XINPUT_GAMEPAD_DPAD_RIGHT != 0 {
+                true => out.push(Event::new(
                     id,
                     EventType::ButtonPressed(crate::native_ev_codes::BTN_DPAD_RIGHT),
                 )),
-                false => tx.send(Event::new(
+                false => out.push(Event::new(
                     id,
                     EventType::ButtonReleased(crate::native_ev_codes::BTN_DPAD_RIGHT),
                 )),
             };
         }
         if !is_mask_eq(g.wButtons, pg.wButtons, XINPUT_GAMEPAD_START) {
-            let _ = match g.wButtons & XINPUT_GAMEPAD_START != 0 {
-                true => tx.send(Event::new(
+            match g.wButtons & XINPUT_GAMEPAD_START != 0 {
+                true => out.push(Event::new(
                     id,
                     EventType::ButtonPressed(crate::native_ev_codes::BTN_START),
                 )),
-                false => tx.send(Event::new(
+                false => out.push(Event::new(
                     id,
                     EventType::ButtonReleased(crate::native_ev_codes::BTN_START),
                 )),
             };
         }
         if !is_mask_eq(g.wButtons, pg.wButtons, XINPUT_GAMEPAD_BACK) {
-            let _ = match g.wButtons & XINPUT_GAMEPAD_BACK != 0 {
-                true => tx.send(Event::new(
+            match g.wButtons & XINPUT_GAMEPAD_BACK != 0 {
+                true => out.push(Event::new(
                     id,
                     EventType::ButtonPressed(crate::native_ev_codes::BTN_SELECT),
                 )),
-                false => tx.send(Event::new(
+                false => out.push(Event::new(
                     id,
                     EventType::ButtonReleased(crate::native_ev_codes::BTN_SELECT),
                 )),
             };
         }
+        // Stick clicks are only reported as button bits, without any analog value. They are
+        // compared on every new packet, also when sticks moved in the same packet. XInput doesn't
+        // queue states, so click shorter than EVENT_THREAD_SLEEP_TIME may still be missed.
         if !is_mask_eq(g.wButtons, pg.wButtons, XINPUT_GAMEPAD_LEFT_THUMB) {
-            let _ = match g.wButtons & XINPUT_GAMEPAD_LEFT_THUMB != 0 {
-                true => tx.send(Event::new(
+            match g.wButtons & XINPUT_GAMEPAD_LEFT_THUMB != 0 {
+                true => out.push(Event::new(
                     id,
                     EventType::ButtonPressed(crate::native_ev_codes::BTN_LTHUMB),
                 )),
-                false => tx.send(Event::new(
+                false => out.push(Event::new(
                     id,
                     EventType::ButtonReleased(crate::native_ev_codes::BTN_LTHUMB),
                 )),
             };
         }
         if !is_mask_eq(g.wButtons, pg.wButtons, XINPUT_GAMEPAD_RIGHT_THUMB) {
-            let _ = match g.wButtons & XINPUT_GAMEPAD_RIGHT_THUMB != 0 {
-                true => tx.send(Event::new(
+            match g.wButtons & XINPUT_GAMEPAD_RIGHT_THUMB != 0 {
+                true => out.push(Event::new(
                     id,
                     EventType::ButtonPressed(crate::native_ev_codes::BTN_RTHUMB),
                 )),
-                false => tx.send(Event::new(
+                false => out.push(Event::new(
                     id,
                     EventType::ButtonReleased(crate::native_ev_codes::BTN_RTHUMB),
                 )),
             };
         }
         if !is_mask_eq(g.wButtons, pg.wButtons, XINPUT_GAMEPAD_LEFT_SHOULDER) {
-            let _ = match g.wButtons & XINPUT_GAMEPAD_LEFT_SHOULDER != 0 {
-                true => tx.send(Event::new(
+            match g.wButtons & XINPUT_GAMEPAD_LEFT_SHOULDER != 0 {
+                true => out.push(Event::new(
                     id,
                     EventType::ButtonPressed(crate::native_ev_codes::BTN_LT),
                 )),
-                false => tx.send(Event::new(
+                false => out.push(Event::new(
                     id,
                     EventType::ButtonReleased(crate::native_ev_codes::BTN_LT),
                 )),
             };
         }
         if !is_mask_eq(g.wButtons, pg.wButtons, XINPUT_GAMEPAD_RIGHT_SHOULDER) {
-            let _ = match g.wButtons & XINPUT_GAMEPAD_RIGHT_SHOULDER != 0 {
-                true => tx.send(Event::new(
+            match g.wButtons & XINPUT_GAMEPAD_RIGHT_SHOULDER != 0 {
+                true => out.push(Event::new(
                     id,
                     EventType::ButtonPressed(crate::native_ev_codes::BTN_RT),
                 )),
-                false => tx.send(Event::new(
+                false => out.push(Event::new(
                     id,
                     EventType::ButtonReleased(crate::native_ev_codes::BTN_RT),
                 )),
             };
         }
         if !is_mask_eq(g.wButtons, pg.wButtons, XINPUT_GAMEPAD_A) {
-            let _ = match g.wButtons & XINPUT_GAMEPAD_A != 0 {
-                true => tx.send(Event::new(
+            match g.wButtons & XINPUT_GAMEPAD_A != 0 {
+                true => out.push(Event::new(
                     id,
                     EventType::ButtonPressed(crate::native_ev_codes::BTN_SOUTH),
                 )),
-                false => tx.send(Event::new(
+                false => out.push(Event::new(
                     id,
                     EventType::ButtonReleased(crate::native_ev_codes::BTN_SOUTH),
                 )),
             };
         }
         if !is_mask_eq(g.wButtons, pg.wButtons, XINPUT_GAMEPAD_B) {
-            let _ = match g.wButtons & XINPUT_GAMEPAD_B != 0 {
-                true => tx.send(Event::new(
+            match g.wButtons & XINPUT_GAMEPAD_B != 0 {
+                true => out.push(Event::new(
                     id,
                     EventType::ButtonPressed(crate::native_ev_codes::BTN_EAST),
                 )),
-                false => tx.send(Event::new(
+                false => out.push(Event::new(
                     id,
                     EventType::ButtonReleased(crate::native_ev_codes::BTN_EAST),
                 )),
             };
         }
         if !is_mask_eq(g.wButtons, pg.wButtons, XINPUT_GAMEPAD_X) {
-            let _ = match g.wButtons & XINPUT_GAMEPAD_X != 0 {
-                true => tx.send(Event::new(
+            match g.wButtons & XINPUT_GAMEPAD_X != 0 {
+                true => out.push(Event::new(
                     id,
                     EventType::ButtonPressed(crate::native_ev_codes::BTN_WEST),
                 )),
-                false => tx.send(Event::new(
+                false => out.push(Event::new(
                     id,
                     EventType::ButtonReleased(crate::native_ev_codes::BTN_WEST),
                 )),
             };
         }
         if !is_mask_eq(g.wButtons, pg.wButtons, XINPUT_GAMEPAD_Y) {
-            let _ = match g.wButtons & XINPUT_GAMEPAD_Y != 0 {
-                true => tx.send(Event::new(
+            match g.wButtons & XINPUT_GAMEPAD_Y != 0 {
+                true => out.push(Event::new(
                     id,
                     EventType::ButtonPressed(crate::native_ev_codes::BTN_NORTH),
                 )),
-                false => tx.send(Event::new(
+                false => out.push(Event::new(
                     id,
                     EventType::ButtonReleased(crate::native_ev_codes::BTN_NORTH),
                 )),
@@ -386,32 +605,215 @@ impl Gilrs {
     }
 }
 
+fn battery_info(xinput_handle: &XInputHandle, id: u32) -> PowerInfo {
+    match xinput_handle.get_gamepad_battery_information(id) {
+        Ok(binfo) => match binfo.battery_type {
+            BatteryType::WIRED => PowerInfo::Wired,
+            BatteryType::ALKALINE | BatteryType::NIMH => {
+                let lvl = match binfo.battery_level {
+                    BatteryLevel::EMPTY => 0,
+                    BatteryLevel::LOW => 33,
+                    BatteryLevel::MEDIUM => 67,
+                    BatteryLevel::FULL => 100,
+                    lvl => {
+                        trace!("Unexpected battery level: {}", lvl.0);
+
+                        100
+                    }
+                };
+                if lvl == 100 {
+                    PowerInfo::Charged
+                } else {
+                    PowerInfo::Discharging(lvl)
+                }
+            }
+            _ => PowerInfo::Unknown,
+        },
+        Err(e) => {
+            debug!("Failed to get battery info: {:?}", e);
+
+            PowerInfo::Unknown
+        }
+    }
+}
+
+/// What XInput tells us about device in slot. It doesn't report serial number or VID/PID, so two
+/// controllers of the same model only differ in packet number, which is counted per device.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct DeviceIdentity {
+    subtype: u8,
+    flags: u16,
+    motors: (u16, u16),
+}
+
+impl DeviceIdentity {
+    fn read(xinput_handle: &XInputHandle, id: u32) -> Option<Self> {
+        xinput_handle
+            .get_capabilities(id)
+            .ok()
+            .map(|caps| DeviceIdentity {
+                subtype: caps.SubType,
+                flags: caps.Flags,
+                motors: (
+                    caps.Vibration.wLeftMotorSpeed,
+                    caps.Vibration.wRightMotorSpeed,
+                ),
+            })
+    }
+}
+
+/// Returns true if device in slot was replaced by other device without slot being reported as
+/// empty in between. Packet number of one device only grows (wrapping after 2^32 packets is
+/// ignored), so lower packet number means that state came from other device.
+fn is_renumbered(
+    prev_packet: u32,
+    packet: u32,
+    prev: Option<DeviceIdentity>,
+    current: Option<DeviceIdentity>,
+) -> bool {
+    if packet < prev_packet {
+        return true;
+    }
+
+    matches!((prev, current), (Some(prev), Some(current)) if prev != current)
+}
+
+/// Decides when empty slot should be checked for newly connected gamepad. Time between checks
+/// doubles after every check that didn't find a gamepad, up to `max_interval` iterations.
+#[derive(Copy, Clone, Debug)]
+struct EmptySlotProbe {
+    interval: u64,
+    max_interval: u64,
+    next: u64,
+}
+
+impl EmptySlotProbe {
+    fn new(max_interval: u64) -> Self {
+        EmptySlotProbe {
+            interval: ITERATIONS_TO_CHECK_IF_CONNECTED,
+            max_interval,
+            next: 0,
+        }
+    }
+
+    fn is_due(&self, counter: u64) -> bool {
+        counter >= self.next
+    }
+
+    fn failed(&mut self, counter: u64) {
+        self.interval = (self.interval * 2).min(self.max_interval);
+        self.next = counter.saturating_add(self.interval);
+    }
+
+    /// Called when gamepad is connected or disconnected.
+    fn reset(&mut self, counter: u64) {
+        self.interval = ITERATIONS_TO_CHECK_IF_CONNECTED;
+        self.next = counter.saturating_add(self.interval);
+    }
+
+    /// Called when some device was added or removed. XInput may need a moment to assign the new
+    /// device to a slot, so we check right away and then in quickly growing intervals.
+    fn wake(&mut self, counter: u64) {
+        self.interval = 1;
+        self.next = counter;
+    }
+}
+
+/// Connection status of XInput slot.
+///
+/// ```text
+///              Connected                Disconnected
+/// NotObserved -----------> Connected -----------------> Disconnected
+///      |                       ^                              |
+///      |                       +------------------------------+
+///      |                                 Connected            ^
+///      +------------------------------------------------------+
+///                             Disconnected
+/// ```
+///
+/// Status of every slot is set in `Gilrs::new()`, from the same check that is used as initial
+/// state of event thread. After that, it's changed only by events from event thread, so it can't
+/// disagree with events that were already returned. `NotObserved` is therefore only seen before
+/// `Gilrs::new()` returns, or for ids that don't belong to any slot.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Status {
+    NotObserved,
+    Connected,
+    Disconnected,
+}
+
+impl Status {
+    /// Returns status after `event`. Events other than `Connected` and `Disconnected` don't
+    /// change it.
+    fn next(self, event: &EventType) -> Status {
+        match (self, event) {
+            (Status::NotObserved | Status::Disconnected, EventType::Connected) => Status::Connected,
+            (Status::NotObserved | Status::Connected, EventType::Disconnected(_)) => {
+                Status::Disconnected
+            }
+            (status, EventType::Connected | EventType::Disconnected(_)) => {
+                warn!(
+                    "Unexpected {:?} event for gamepad with status {:?}",
+                    event, status
+                );
+                status
+            }
+            (status, _) => status,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Gamepad {
     uuid: Uuid,
     id: u32,
-    is_connected: bool,
+    status: Status,
+    subtype: DeviceSubtype,
+    has_rumble: bool,
+    power_info: PowerInfo,
     xinput_handle: Arc<XInputHandle>,
 }
 
 impl Gamepad {
     fn new(id: u32, xinput_handle: Arc<XInputHandle>) -> Gamepad {
-        let is_connected = {
-            if xinput_handle.get_state(id).is_ok() {
-                true
-            } else {
-                false
-            }
-        };
-
-        let gamepad = Gamepad {
+        Gamepad {
             uuid: Uuid::nil(),
             id,
-            is_connected,
+            status: Status::NotObserved,
+            subtype: DeviceSubtype::Unknown,
+            has_rumble: true,
+            power_info: PowerInfo::Unknown,
             xinput_handle,
-        };
+        }
+    }
+
+    fn handle_event(&mut self, event: &EventType) {
+        let status = self.status.next(event);
+        if status == Status::Connected && self.status != Status::Connected {
+            self.update_capabilities();
+        }
+        match *event {
+            EventType::PowerChanged(info) => self.power_info = info,
+            EventType::Disconnected(_) => self.power_info = PowerInfo::Unknown,
+            _ => (),
+        }
+        self.status = status;
+    }
 
-        gamepad
+    fn update_capabilities(&mut self) {
+        match self.xinput_handle.get_capabilities(self.id) {
+            Ok(caps) => {
+                self.subtype = subtype_from_xinput(caps.SubType);
+                self.has_rumble =
+                    caps.Vibration.wLeftMotorSpeed != 0 || caps.Vibration.wRightMotorSpeed != 0;
+            }
+            Err(e) => {
+                // Keep old behaviour and let FF thread find out.
+                debug!("Failed to get gamepad capabilities: {:?}", e);
+                self.subtype = DeviceSubtype::Unknown;
+                self.has_rumble = true;
+            }
+        }
     }
 
     pub fn name(&self) -> &str {
@@ -434,44 +836,35 @@ impl Gamepad {
         None
     }
 
+    pub fn version(&self) -> Option<u16> {
+        None
+    }
+
+    pub fn set_player_index(&self, _index: Option<u8>) -> io::Result<()> {
+        // XInput lights LED of the slot gamepad is assigned to and doesn't allow changing it.
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
     pub fn is_connected(&self) -> bool {
-        self.is_connected
+        self.status == Status::Connected
     }
 
+    /// Returns power information cached by event thread.
     pub fn power_info(&self) -> PowerInfo {
-        match self.xinput_handle.get_gamepad_battery_information(self.id) {
-            Ok(binfo) => match binfo.battery_type {
-                BatteryType::WIRED => PowerInfo::Wired,
-                BatteryType::ALKALINE | BatteryType::NIMH => {
-                    let lvl = match binfo.battery_level {
-                        BatteryLevel::EMPTY => 0,
-                        BatteryLevel::LOW => 33,
-                        BatteryLevel::MEDIUM => 67,
-                        BatteryLevel::FULL => 100,
-                        lvl => {
-                            trace!("Unexpected battery level: {}", lvl.0);
-
-                            100
-                        }
-                    };
-                    if lvl == 100 {
-                        PowerInfo::Charged
-                    } else {
-                        PowerInfo::Discharging(lvl)
-                    }
-                }
-                _ => PowerInfo::Unknown,
-            },
-            Err(e) => {
-                debug!("Failed to get battery info: {:?}", e);
-
-                PowerInfo::Unknown
-            }
-        }
+        self.power_info
     }
 
     pub fn is_ff_supported(&self) -> bool {
-        true
+        self.has_rumble
+    }
+
+    pub fn subtype(&self) -> DeviceSubtype {
+        self.subtype
+    }
+
+    pub fn has_trigger_rumble(&self) -> bool {
+        // XInput can only drive the two main motors.
+        false
     }
 
     pub fn ff_device(&self) -> Option<FfDevice> {
@@ -493,6 +886,21 @@ impl Gamepad {
     }
 }
 
+// XINPUT_DEVSUBTYPE_* values. Some of them are only reported by XInput 1.4.
+fn subtype_from_xinput(subtype: u8) -> DeviceSubtype {
+    match subtype {
+        0x01 => DeviceSubtype::Gamepad,
+        0x02 => DeviceSubtype::Wheel,
+        0x03 => DeviceSubtype::ArcadeStick,
+        0x04 => DeviceSubtype::FlightStick,
+        0x05 => DeviceSubtype::DancePad,
+        0x06 | 0x07 | 0x0B => DeviceSubtype::Guitar,
+        0x08 => DeviceSubtype::DrumKit,
+        0x13 => DeviceSubtype::ArcadePad,
+        _ => DeviceSubtype::Unknown,
+    }
+}
+
 #[inline(always)]
 fn is_mask_eq(l: u16, r: u16, mask: u16) -> bool {
     (l & mask != 0) == (r & mask != 0)
@@ -580,6 +988,13 @@ pub mod native_ev_codes {
     pub const BTN_DPAD_LEFT: EvCode = EvCode(29);
     pub const BTN_DPAD_RIGHT: EvCode = EvCode(30);
 
+    // Not reported by this backend.
+    pub const BTN_SHARE: EvCode = EvCode(31);
+    pub const AXIS_STEERING: EvCode = EvCode(32);
+    pub const AXIS_THROTTLE: EvCode = EvCode(33);
+    pub const AXIS_BRAKE: EvCode = EvCode(34);
+    pub const AXIS_CLUTCH: EvCode = EvCode(35);
+
     pub(super) static BUTTONS: [EvCode; 15] = [
         BTN_SOUTH,
         BTN_EAST,
@@ -658,3 +1073,123 @@ pub mod native_ev_codes {
         }),
     ];
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        is_renumbered, native_ev_codes as nec, subtype_from_xinput, DeviceIdentity, EmptySlotProbe,
+        Gilrs, Status, ITERATIONS_TO_CHECK_IF_CONNECTED, MAX_ITERATIONS_TO_CHECK_IF_CONNECTED,
+    };
+    use crate::{DeviceSubtype, DisconnectReason, Event, EventType};
+    use winapi::um::xinput::XINPUT_GAMEPAD_TRIGGER_THRESHOLD;
+
+    #[test]
+    fn trigger_thresholds_are_symmetric() {
+        let deadzone = |code: super::EvCode| nec::AXES_INFO[code.0 as usize].unwrap().deadzone;
+
+        assert_eq!(
+            deadzone(nec::AXIS_LT2),
+            Some(XINPUT_GAMEPAD_TRIGGER_THRESHOLD as u32)
+        );
+        assert_eq!(deadzone(nec::AXIS_LT2), deadzone(nec::AXIS_RT2));
+    }
+
+    #[test]
+    fn interleave_slots() {
+        let events = |id, n| vec![Event::new(id, EventType::Connected); n];
+        let mut queues = [events(0, 3), events(1, 1), Vec::new(), events(3, 2)];
+
+        let mut ids = Vec::new();
+        Gilrs::interleave(&mut queues, 1, |ev| ids.push(ev.id));
+        assert_eq!(ids, [1, 3, 0, 3, 0, 0]);
+        assert!(queues.iter().all(Vec::is_empty));
+    }
+
+    #[test]
+    fn empty_slot_backoff() {
+        let mut probe = EmptySlotProbe::new(MAX_ITERATIONS_TO_CHECK_IF_CONNECTED);
+        assert!(probe.is_due(0));
+
+        probe.failed(0);
+        assert!(!probe.is_due(2 * ITERATIONS_TO_CHECK_IF_CONNECTED - 1));
+        assert!(probe.is_due(2 * ITERATIONS_TO_CHECK_IF_CONNECTED));
+
+        let mut counter = 0;
+        for _ in 0..10 {
+            probe.failed(counter);
+            counter = probe.next;
+        }
+        assert_eq!(probe.interval, MAX_ITERATIONS_TO_CHECK_IF_CONNECTED);
+
+        probe.reset(counter);
+        assert_eq!(probe.interval, ITERATIONS_TO_CHECK_IF_CONNECTED);
+        assert!(!probe.is_due(counter));
+        assert!(probe.is_due(counter + ITERATIONS_TO_CHECK_IF_CONNECTED));
+    }
+
+    #[test]
+    fn empty_slot_wake() {
+        let mut probe = EmptySlotProbe::new(MAX_ITERATIONS_TO_CHECK_IF_CONNECTED);
+        probe.failed(0);
+        assert!(!probe.is_due(10));
+
+        probe.wake(10);
+        assert!(probe.is_due(10));
+        probe.failed(10);
+        assert!(probe.is_due(12));
+        probe.failed(12);
+        assert!(!probe.is_due(15));
+        assert!(probe.is_due(16));
+    }
+
+    #[test]
+    fn slot_renumbering() {
+        let pad = DeviceIdentity {
+            subtype: 1,
+            flags: 0,
+            motors: (255, 255),
+        };
+        let wheel = DeviceIdentity { subtype: 2, ..pad };
+
+        // Same device, new input.
+        assert!(!is_renumbered(100, 101, Some(pad), Some(pad)));
+        assert!(!is_renumbered(100, 100, Some(pad), None));
+        // Controller of the same model moved from other slot, it sent less packets.
+        assert!(is_renumbered(100, 20, Some(pad), Some(pad)));
+        // Different device moved from other slot.
+        assert!(is_renumbered(100, 150, Some(pad), Some(wheel)));
+        assert!(!is_renumbered(100, 150, None, Some(wheel)));
+    }
+
+    #[test]
+    fn xinput_subtype() {
+        assert_eq!(subtype_from_xinput(0x01), DeviceSubtype::Gamepad);
+        assert_eq!(subtype_from_xinput(0x07), DeviceSubtype::Guitar);
+        assert_eq!(subtype_from_xinput(0x13), DeviceSubtype::ArcadePad);
+        assert_eq!(subtype_from_xinput(0x00), DeviceSubtype::Unknown);
+    }
+
+    #[test]
+    fn status_transitions() {
+        let connected = EventType::Connected;
+        let disconnected = EventType::Disconnected(DisconnectReason::Removed);
+        let other = EventType::ButtonPressed(crate::native_ev_codes::BTN_SOUTH);
+
+        assert_eq!(Status::NotObserved.next(&connected), Status::Connected);
+        assert_eq!(
+            Status::NotObserved.next(&disconnected),
+            Status::Disconnected
+        );
+        assert_eq!(Status::Connected.next(&disconnected), Status::Disconnected);
+        assert_eq!(Status::Disconnected.next(&connected), Status::Connected);
+
+        // Repeated or unrelated events don't change status.
+        assert_eq!(Status::Connected.next(&connected), Status::Connected);
+        assert_eq!(
+            Status::Disconnected.next(&disconnected),
+            Status::Disconnected
+        );
+        assert_eq!(Status::Connected.next(&other), Status::Connected);
+        assert_eq!(Status::NotObserved.next(&other), Status::NotObserved);
+    }
+}
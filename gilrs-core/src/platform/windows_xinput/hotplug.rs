@@ -0,0 +1,146 @@
+// Copyright 2016-2018 Mateusz Sieczko and other GilRs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Device arrival and removal notifications.
+//!
+//! XInput doesn't tell us when gamepad is connected, so we create message-only window, register it
+//! for `WM_DEVICECHANGE` and count received notifications. Event thread checks empty slots when
+//! the count changes.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Once};
+use std::{mem, ptr, thread};
+
+use winapi::shared::minwindef::{DWORD, LPARAM, LPVOID, LRESULT, UINT, WPARAM};
+use winapi::shared::windef::HWND;
+use winapi::um::dbt::{
+    DBT_DEVICEARRIVAL, DBT_DEVICEREMOVECOMPLETE, DBT_DEVTYP_DEVICEINTERFACE,
+    DEV_BROADCAST_DEVICEINTERFACE_W,
+};
+use winapi::um::libloaderapi::GetModuleHandleW;
+use winapi::um::winnt::HANDLE;
+use winapi::um::winuser::{
+    CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, GetMessageW,
+    RegisterClassExW, RegisterDeviceNotificationW, TranslateMessage,
+    DEVICE_NOTIFY_ALL_INTERFACE_CLASSES, DEVICE_NOTIFY_WINDOW_HANDLE, HWND_MESSAGE, MSG,
+    WM_DEVICECHANGE, WNDCLASSEXW,
+};
+
+static START: Once = Once::new();
+static AVAILABLE: AtomicBool = AtomicBool::new(false);
+static DEVICE_CHANGES: AtomicUsize = AtomicUsize::new(0);
+
+/// Starts listening for device changes, if it's not already started. Returns `false` if
+/// notifications are not available and empty slots have to be polled.
+pub(super) fn start() -> bool {
+    START.call_once(|| {
+        let (tx, rx) = mpsc::channel();
+        let spawned = thread::Builder::new()
+            .name("gilrs hotplug".to_owned())
+            .spawn(move || unsafe {
+                let hwnd = match create_window() {
+                    Some(hwnd) => hwnd,
+                    None => {
+                        let _ = tx.send(false);
+                        return;
+                    }
+                };
+                let _ = tx.send(true);
+
+                let mut msg: MSG = mem::zeroed();
+                while GetMessageW(&mut msg, hwnd, 0, 0) > 0 {
+                    TranslateMessage(&msg);
+                    DispatchMessageW(&msg);
+                }
+            });
+
+        let available = spawned.is_ok() && rx.recv().unwrap_or(false);
+        if !available {
+            warn!("Failed to register for device notifications, falling back to polling");
+        }
+        AVAILABLE.store(available, Ordering::Release);
+    });
+
+    AVAILABLE.load(Ordering::Acquire)
+}
+
+/// Returns number of device arrivals and removals since notifications were started.
+pub(super) fn changes() -> usize {
+    DEVICE_CHANGES.load(Ordering::Acquire)
+}
+
+unsafe fn create_window() -> Option<HWND> {
+    let class_name: Vec<u16> = "gilrs_xinput_hotplug\0".encode_utf16().collect();
+    let instance = GetModuleHandleW(ptr::null());
+
+    let mut class: WNDCLASSEXW = mem::zeroed();
+    class.cbSize = mem::size_of::<WNDCLASSEXW>() as UINT;
+    class.lpfnWndProc = Some(window_proc);
+    class.hInstance = instance;
+    class.lpszClassName = class_name.as_ptr();
+
+    if RegisterClassExW(&class) == 0 {
+        error!("RegisterClassExW() failed");
+        return None;
+    }
+
+    let hwnd = CreateWindowExW(
+        0,
+        class_name.as_ptr(),
+        ptr::null(),
+        0,
+        0,
+        0,
+        0,
+        0,
+        HWND_MESSAGE,
+        ptr::null_mut(),
+        instance,
+        ptr::null_mut(),
+    );
+
+    if hwnd.is_null() {
+        error!("CreateWindowExW() failed");
+        return None;
+    }
+
+    let mut filter: DEV_BROADCAST_DEVICEINTERFACE_W = mem::zeroed();
+    filter.dbcc_size = mem::size_of::<DEV_BROADCAST_DEVICEINTERFACE_W>() as DWORD;
+    filter.dbcc_devicetype = DBT_DEVTYP_DEVICEINTERFACE;
+
+    let notify = RegisterDeviceNotificationW(
+        hwnd as HANDLE,
+        &mut filter as *mut _ as LPVOID,
+        DEVICE_NOTIFY_WINDOW_HANDLE | DEVICE_NOTIFY_ALL_INTERFACE_CLASSES,
+    );
+
+    if notify.is_null() {
+        error!("RegisterDeviceNotificationW() failed");
+        DestroyWindow(hwnd);
+        return None;
+    }
+
+    Some(hwnd)
+}
+
+unsafe extern "system" fn window_proc(
+    hwnd: HWND,
+    msg: UINT,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    if msg == WM_DEVICECHANGE {
+        match wparam {
+            DBT_DEVICEARRIVAL | DBT_DEVICEREMOVECOMPLETE => {
+                DEVICE_CHANGES.fetch_add(1, Ordering::AcqRel);
+            }
+            _ => (),
+        }
+    }
+
+    DefWindowProcW(hwnd, msg, wparam, lparam)
+}
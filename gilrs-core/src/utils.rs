@@ -1,4 +1,7 @@
-use std::time::SystemTime;
+use std::sync::mpsc::{Receiver, RecvTimeoutError, TryRecvError};
+use std::time::{Duration, SystemTime};
+
+use uuid::Uuid;
 
 /// Returns true if nth bit in array is 1.
 #[allow(dead_code)]
@@ -6,6 +9,90 @@ pub(crate) fn test_bit(n: u16, array: &[u8]) -> bool {
     (array[(n / 8) as usize] >> (n % 8)) & 1 != 0
 }
 
+/// Bus type used by SDL for USB devices.
+#[allow(dead_code)]
+pub(crate) const SDL_HARDWARE_BUS_USB: u16 = 0x03;
+
+/// Creates UUID with the same layout as SDL2 joystick GUID, which is also used by
+/// `gamecontrollerdb.txt`.
+///
+/// The GUID is made of eight little endian 16-bit words:
+///
+/// | bytes  | content                              |
+/// |--------|--------------------------------------|
+/// | 0..2   | bus type (for example `0x03` - USB)  |
+/// | 2..4   | CRC16 of name, always 0 here         |
+/// | 4..6   | vendor ID                            |
+/// | 6..8   | 0                                    |
+/// | 8..10  | product ID                           |
+/// | 10..12 | 0                                    |
+/// | 12..14 | version                              |
+/// | 14..16 | driver signature and data, always 0  |
+#[allow(dead_code)]
+pub(crate) fn sdl_uuid(bustype: u16, vendor: u16, product: u16, version: u16) -> Uuid {
+    let mut bytes = [0u8; 16];
+    bytes[0..2].copy_from_slice(&bustype.to_le_bytes());
+    bytes[4..6].copy_from_slice(&vendor.to_le_bytes());
+    bytes[8..10].copy_from_slice(&product.to_le_bytes());
+    bytes[12..14].copy_from_slice(&version.to_le_bytes());
+
+    Uuid::from_bytes(bytes)
+}
+
+/// Receiving end of channel used by backend's event thread. Remembers if the thread is gone (for
+/// example because it panicked), so backend can report it instead of silently returning no events.
+#[allow(dead_code)]
+#[derive(Debug)]
+pub(crate) struct EventReceiver<T> {
+    rx: Receiver<T>,
+    alive: bool,
+}
+
+#[allow(dead_code)]
+impl<T> EventReceiver<T> {
+    pub fn new(rx: Receiver<T>) -> Self {
+        EventReceiver { rx, alive: true }
+    }
+
+    /// Returns false if event thread stopped and no more events will be received.
+    pub fn is_alive(&self) -> bool {
+        self.alive
+    }
+
+    pub fn try_recv(&mut self) -> Option<T> {
+        match self.rx.try_recv() {
+            Ok(ev) => Some(ev),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => self.died(),
+        }
+    }
+
+    /// Waits for event, up to `timeout` if it's `Some`. Returns immediately if event thread is
+    /// gone.
+    pub fn recv(&mut self, timeout: Option<Duration>) -> Option<T> {
+        match timeout {
+            Some(timeout) => match self.rx.recv_timeout(timeout) {
+                Ok(ev) => Some(ev),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => self.died(),
+            },
+            None => match self.rx.recv() {
+                Ok(ev) => Some(ev),
+                Err(_) => self.died(),
+            },
+        }
+    }
+
+    fn died(&mut self) -> Option<T> {
+        if self.alive {
+            error!("Event thread stopped, no more gamepad events will be received");
+            self.alive = false;
+        }
+
+        None
+    }
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 pub fn time_now() -> SystemTime {
     SystemTime::now()
@@ -19,3 +106,40 @@ pub fn time_now() -> SystemTime {
     let offset = Duration::from_millis(Date::now() as u64);
     SystemTime::UNIX_EPOCH + offset
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{sdl_uuid, EventReceiver, SDL_HARDWARE_BUS_USB};
+    use std::sync::mpsc;
+    use std::thread;
+    use uuid::Uuid;
+
+    #[test]
+    fn sdl_uuid_layout() {
+        // Xbox 360 controller, as in gamecontrollerdb.txt
+        let x = Uuid::parse_str("030000005e0400008e02000014010000").unwrap();
+        assert_eq!(x, sdl_uuid(SDL_HARDWARE_BUS_USB, 0x045e, 0x028e, 0x0114));
+
+        // Sony DualShock 4 over bluetooth
+        let x = Uuid::parse_str("050000004c050000cc09000000810000").unwrap();
+        assert_eq!(x, sdl_uuid(0x05, 0x054c, 0x09cc, 0x8100));
+    }
+
+    #[test]
+    fn dead_event_thread() {
+        let (tx, rx) = mpsc::channel();
+        let mut rx = EventReceiver::new(rx);
+        let thread = thread::spawn(move || {
+            tx.send(1).unwrap();
+            panic!("event thread failed");
+        });
+        assert!(thread.join().is_err());
+
+        // Events sent before the thread died are still received.
+        assert!(rx.is_alive());
+        assert_eq!(rx.try_recv(), Some(1));
+        assert_eq!(rx.try_recv(), None);
+        assert!(!rx.is_alive());
+        assert_eq!(rx.recv(None), None);
+    }
+}
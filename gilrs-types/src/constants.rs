@@ -28,6 +28,8 @@ pub const BTN_DPAD_DOWN: u16 = 17;
 pub const BTN_DPAD_LEFT: u16 = 18;
 pub const BTN_DPAD_RIGHT: u16 = 19;
 
+pub const BTN_SHARE: u16 = 20;
+
 pub const AXIS_UNKNOWN: u16 = 0;
 
 pub const AXIS_LSTICKX: u16 = 1;
@@ -38,3 +40,8 @@ pub const AXIS_RSTICKY: u16 = 5;
 pub const AXIS_RIGHTZ: u16 = 6;
 pub const AXIS_DPADX: u16 = 7;
 pub const AXIS_DPADY: u16 = 8;
+
+pub const AXIS_STEERING: u16 = 9;
+pub const AXIS_THROTTLE: u16 = 10;
+pub const AXIS_BRAKE: u16 = 11;
+pub const AXIS_CLUTCH: u16 = 12;
@@ -0,0 +1,463 @@
+// Copyright 2016-2018 Mateusz Sieczko and other GilRs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Gamepad's elements: buttons, axes and sides.
+
+use crate::constants::*;
+
+use core::fmt::{self, Display, Formatter};
+use core::str::FromStr;
+
+#[cfg(feature = "serde-serialize")]
+use serde::{Deserialize, Serialize};
+
+#[repr(u16)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+/// Gamepad's elements which state can be represented by value from 0.0 to 1.0.
+///
+/// ![Controller layout](https://gilrs-project.gitlab.io/gilrs/img/controller.svg)
+pub enum Button {
+    // Action Pad
+    South = BTN_SOUTH,
+    East = BTN_EAST,
+    North = BTN_NORTH,
+    West = BTN_WEST,
+    C = BTN_C,
+    Z = BTN_Z,
+    // Triggers
+    LeftTrigger = BTN_LT,
+    LeftTrigger2 = BTN_LT2,
+    RightTrigger = BTN_RT,
+    RightTrigger2 = BTN_RT2,
+    // Menu Pad
+    Select = BTN_SELECT,
+    Start = BTN_START,
+    Mode = BTN_MODE,
+    // Sticks
+    /// Left stick click. It's digital on all supported gamepads, so its value is always 0.0 or
+    /// 1.0 and it can't tell how hard stick is pushed.
+    LeftThumb = BTN_LTHUMB,
+    /// Right stick click. See [`LeftThumb`](Button::LeftThumb).
+    RightThumb = BTN_RTHUMB,
+    // D-Pad
+    DPadUp = BTN_DPAD_UP,
+    DPadDown = BTN_DPAD_DOWN,
+    DPadLeft = BTN_DPAD_LEFT,
+    DPadRight = BTN_DPAD_RIGHT,
+    // Misc
+    /// Share/capture button of Xbox Series X|S controllers. Reported on Linux (xpad driver) and by
+    /// gamepads with SDL mapping for `misc1`. XInput, Windows Gaming Input, macOS and web
+    /// backends can't read it, so they never emit it.
+    Share = BTN_SHARE,
+
+    #[default]
+    Unknown = BTN_UNKNOWN,
+}
+
+static ALL_BUTTONS: [Button; 20] = [
+    Button::South,
+    Button::East,
+    Button::North,
+    Button::West,
+    Button::C,
+    Button::Z,
+    Button::LeftTrigger,
+    Button::LeftTrigger2,
+    Button::RightTrigger,
+    Button::RightTrigger2,
+    Button::Select,
+    Button::Start,
+    Button::Mode,
+    Button::LeftThumb,
+    Button::RightThumb,
+    Button::DPadUp,
+    Button::DPadDown,
+    Button::DPadLeft,
+    Button::DPadRight,
+    Button::Share,
+];
+
+impl Button {
+    /// Returns iterator over all buttons except `Unknown`.
+    pub fn all() -> impl Iterator<Item = Button> + Clone {
+        ALL_BUTTONS.iter().copied()
+    }
+
+    pub fn is_action(self) -> bool {
+        use Button::*;
+        matches!(self, South | East | North | West | C | Z)
+    }
+
+    pub fn is_trigger(self) -> bool {
+        use Button::*;
+        matches!(
+            self,
+            LeftTrigger | LeftTrigger2 | RightTrigger | RightTrigger2
+        )
+    }
+
+    pub fn is_menu(self) -> bool {
+        use Button::*;
+        matches!(self, Select | Start | Mode)
+    }
+
+    pub fn is_stick(self) -> bool {
+        use Button::*;
+        matches!(self, LeftThumb | RightThumb)
+    }
+
+    pub fn is_dpad(self) -> bool {
+        use Button::*;
+        matches!(self, DPadUp | DPadDown | DPadLeft | DPadRight)
+    }
+
+    /// Returns name of button, same as name of the variant. It's used by `Display` and `FromStr`
+    /// implementations and won't change.
+    pub fn name(self) -> &'static str {
+        use Button::*;
+        match self {
+            South => "South",
+            East => "East",
+            North => "North",
+            West => "West",
+            C => "C",
+            Z => "Z",
+            LeftTrigger => "LeftTrigger",
+            LeftTrigger2 => "LeftTrigger2",
+            RightTrigger => "RightTrigger",
+            RightTrigger2 => "RightTrigger2",
+            Select => "Select",
+            Start => "Start",
+            Mode => "Mode",
+            LeftThumb => "LeftThumb",
+            RightThumb => "RightThumb",
+            DPadUp => "DPadUp",
+            DPadDown => "DPadDown",
+            DPadLeft => "DPadLeft",
+            DPadRight => "DPadRight",
+            Share => "Share",
+            Unknown => "Unknown",
+        }
+    }
+}
+
+impl Display for Button {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Button {
+    type Err = ParseElementError;
+
+    /// Parses name returned by [`Button::name()`]. Names are case sensitive.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Button::all()
+            .chain(Some(Button::Unknown))
+            .find(|btn| btn.name() == s)
+            .ok_or(ParseElementError)
+    }
+}
+
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+/// Gamepad's elements which state can be represented by value from -1.0 to 1.0.
+///
+/// ![Controller layout](https://gilrs-project.gitlab.io/gilrs/img/controller.svg)
+///
+/// Axes that aren't described by any variant are reported as `Unknown`. They can be told apart by
+/// their `gilrs::ev::Code` and listed with `gilrs::Gamepad::axes()`.
+/// Gears of racing wheel's H-pattern shifter are buttons and are reported as `Button::Unknown`.
+pub enum Axis {
+    LeftStickX = AXIS_LSTICKX,
+    LeftStickY = AXIS_LSTICKY,
+    LeftZ = AXIS_LEFTZ,
+    RightStickX = AXIS_RSTICKX,
+    RightStickY = AXIS_RSTICKY,
+    RightZ = AXIS_RIGHTZ,
+    DPadX = AXIS_DPADX,
+    DPadY = AXIS_DPADY,
+    // Racing wheels
+    /// Steering wheel, -1.0 is full left.
+    Steering = AXIS_STEERING,
+    /// Accelerator pedal. Pedals are -1.0 when released and 1.0 when fully pressed.
+    Throttle = AXIS_THROTTLE,
+    /// Brake pedal.
+    Brake = AXIS_BRAKE,
+    /// Clutch pedal. Only macOS reports it by default, on other platforms it can be set with
+    /// `gilrs::Gilrs::set_mapping()`.
+    Clutch = AXIS_CLUTCH,
+    Unknown = AXIS_UNKNOWN,
+}
+
+static ALL_AXES: [Axis; 12] = [
+    Axis::LeftStickX,
+    Axis::LeftStickY,
+    Axis::LeftZ,
+    Axis::RightStickX,
+    Axis::RightStickY,
+    Axis::RightZ,
+    Axis::DPadX,
+    Axis::DPadY,
+    Axis::Steering,
+    Axis::Throttle,
+    Axis::Brake,
+    Axis::Clutch,
+];
+
+impl Axis {
+    /// Returns iterator over all axes except `Unknown`.
+    pub fn all() -> impl Iterator<Item = Axis> + Clone {
+        ALL_AXES.iter().copied()
+    }
+
+    /// Returns true if axis is `LeftStickX`, `LeftStickY`, `RightStickX` or `RightStickY`.
+    pub fn is_stick(self) -> bool {
+        use Axis::*;
+        matches!(self, LeftStickX | LeftStickY | RightStickX | RightStickY)
+    }
+
+    /// Returns the other axis from same element of gamepad, if any.
+    ///
+    /// | input       | output            |
+    /// |-------------|-------------------|
+    /// |`LeftStickX` |`Some(LeftStickY)` |
+    /// |`LeftStickY` |`Some(LeftStickX)` |
+    /// |`RightStickX`|`Some(RightStickY)`|
+    /// |`RightStickY`|`Some(RightStickX)`|
+    /// |`DpadX`      |`Some(DpadY)`      |
+    /// |`DpadY`      |`Some(DpadX)`      |
+    /// | …           |`None`             |
+    pub fn second_axis(self) -> Option<Self> {
+        use Axis::*;
+        match self {
+            LeftStickX => Some(LeftStickY),
+            LeftStickY => Some(LeftStickX),
+            RightStickX => Some(RightStickY),
+            RightStickY => Some(RightStickX),
+            DPadX => Some(DPadY),
+            DPadY => Some(DPadX),
+            _ => None,
+        }
+    }
+
+    /// Returns name of axis, same as name of the variant. It's used by `Display` and `FromStr`
+    /// implementations and won't change.
+    pub fn name(self) -> &'static str {
+        use Axis::*;
+        match self {
+            LeftStickX => "LeftStickX",
+            LeftStickY => "LeftStickY",
+            LeftZ => "LeftZ",
+            RightStickX => "RightStickX",
+            RightStickY => "RightStickY",
+            RightZ => "RightZ",
+            DPadX => "DPadX",
+            DPadY => "DPadY",
+            Steering => "Steering",
+            Throttle => "Throttle",
+            Brake => "Brake",
+            Clutch => "Clutch",
+            Unknown => "Unknown",
+        }
+    }
+}
+
+impl Display for Axis {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Axis {
+    type Err = ParseElementError;
+
+    /// Parses name returned by [`Axis::name()`]. Names are case sensitive.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Axis::all()
+            .chain(Some(Axis::Unknown))
+            .find(|axis| axis.name() == s)
+            .ok_or(ParseElementError)
+    }
+}
+
+/// Error returned when string is not a name of `Button` or `Axis`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ParseElementError;
+
+impl Display for ParseElementError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("not a name of gamepad element")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseElementError {}
+
+/// Represents `Axis` or `Button`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub enum AxisOrBtn {
+    Axis(Axis),
+    Btn(Button),
+}
+
+impl AxisOrBtn {
+    /// Returns true if this is a button.
+    pub fn is_button(&self) -> bool {
+        matches!(self, AxisOrBtn::Btn(_))
+    }
+}
+
+/// Left or right half of gamepad.
+///
+/// Useful for control schemes that treat both sticks or both triggers the same way. See
+/// `gilrs::Gamepad::stick()`, `gilrs::Gamepad::trigger()` and `gilrs::Gamepad::shoulder()`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub enum Side {
+    Left,
+    Right,
+}
+
+impl Side {
+    /// Returns the other side.
+    pub fn opposite(self) -> Self {
+        match self {
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+        }
+    }
+
+    /// Returns X and Y axes of stick on this side.
+    pub fn stick_axes(self) -> (Axis, Axis) {
+        match self {
+            Side::Left => (Axis::LeftStickX, Axis::LeftStickY),
+            Side::Right => (Axis::RightStickX, Axis::RightStickY),
+        }
+    }
+
+    /// Returns analog trigger on this side (`LeftTrigger2` or `RightTrigger2`).
+    pub fn trigger(self) -> Button {
+        match self {
+            Side::Left => Button::LeftTrigger2,
+            Side::Right => Button::RightTrigger2,
+        }
+    }
+
+    /// Returns shoulder button on this side (`LeftTrigger` or `RightTrigger`).
+    pub fn shoulder(self) -> Button {
+        match self {
+            Side::Left => Button::LeftTrigger,
+            Side::Right => Button::RightTrigger,
+        }
+    }
+
+    /// Returns stick button on this side (`LeftThumb` or `RightThumb`).
+    pub fn thumb(self) -> Button {
+        match self {
+            Side::Left => Button::LeftThumb,
+            Side::Right => Button::RightThumb,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Axis, Button, ParseElementError, Side};
+
+    #[test]
+    fn side_stick_axes() {
+        for side in [Side::Left, Side::Right] {
+            let (x, y) = side.stick_axes();
+            assert!(x.is_stick() && y.is_stick());
+            assert_eq!(x.second_axis(), Some(y));
+            assert_ne!(side.stick_axes(), side.opposite().stick_axes());
+        }
+        assert_eq!(Side::Left.stick_axes().0, Axis::LeftStickX);
+        assert!(Side::Right.trigger().is_trigger());
+        assert!(Side::Right.shoulder().is_trigger());
+        assert!(Side::Left.thumb().is_stick());
+    }
+
+    #[test]
+    fn all_buttons() {
+        let buttons: Vec<Button> = Button::all().collect();
+        for (i, btn) in buttons.iter().enumerate() {
+            assert!(!buttons[i + 1..].contains(btn));
+            // Fails to compile when new variant is added, reminding to update `ALL_BUTTONS`.
+            match *btn {
+                Button::South
+                | Button::East
+                | Button::North
+                | Button::West
+                | Button::C
+                | Button::Z
+                | Button::LeftTrigger
+                | Button::LeftTrigger2
+                | Button::RightTrigger
+                | Button::RightTrigger2
+                | Button::Select
+                | Button::Start
+                | Button::Mode
+                | Button::LeftThumb
+                | Button::RightThumb
+                | Button::DPadUp
+                | Button::DPadDown
+                | Button::DPadLeft
+                | Button::DPadRight
+                | Button::Share => (),
+                Button::Unknown => panic!("Button::all() returned Unknown"),
+            }
+        }
+        assert_eq!(buttons.len(), 20);
+    }
+
+    #[test]
+    fn names_round_trip() {
+        for btn in Button::all().chain(Some(Button::Unknown)) {
+            assert_eq!(btn.to_string().parse(), Ok(btn));
+        }
+        for axis in Axis::all().chain(Some(Axis::Unknown)) {
+            assert_eq!(axis.to_string().parse(), Ok(axis));
+        }
+
+        assert_eq!(Button::LeftTrigger2.to_string(), "LeftTrigger2");
+        assert_eq!(Axis::LeftStickX.to_string(), "LeftStickX");
+        assert_eq!("south".parse::<Button>(), Err(ParseElementError));
+        assert_eq!("South ".parse::<Button>(), Err(ParseElementError));
+        assert_eq!("South".parse::<Axis>(), Err(ParseElementError));
+    }
+
+    #[test]
+    fn all_axes() {
+        let axes: Vec<Axis> = Axis::all().collect();
+        for (i, axis) in axes.iter().enumerate() {
+            assert!(!axes[i + 1..].contains(axis));
+            match *axis {
+                Axis::LeftStickX
+                | Axis::LeftStickY
+                | Axis::LeftZ
+                | Axis::RightStickX
+                | Axis::RightStickY
+                | Axis::RightZ
+                | Axis::DPadX
+                | Axis::DPadY
+                | Axis::Steering
+                | Axis::Throttle
+                | Axis::Brake
+                | Axis::Clutch => (),
+                Axis::Unknown => panic!("Axis::all() returned Unknown"),
+            }
+        }
+        assert_eq!(axes.len(), 12);
+    }
+}
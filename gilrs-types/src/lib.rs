@@ -0,0 +1,49 @@
+// Copyright 2016-2018 Mateusz Sieczko and other GilRs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Gamepad element types of GilRs.
+//!
+//! This crate contains types that describe gamepad's elements and state without depending on
+//! platform backend or `std`, so they can be used in `no_std` code, for example in firmware that
+//! sends input to machine running `gilrs`. Types that need clock or platform specific event codes
+//! (`Event`, `Code`) are only available in `gilrs`, which reexports everything from this crate.
+//!
+//! ## Features
+//!
+//! * `std` (enabled by default) – implements `std::error::Error` for error types. Disable it with
+//!   `default-features = false` to build without `std`.
+//! * `serde-serialize` – all types can be serialized and deserialized with `serde`.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[doc(hidden)]
+pub mod constants;
+mod ev;
+
+pub use crate::ev::{Axis, AxisOrBtn, Button, ParseElementError, Side};
+
+#[cfg(feature = "serde-serialize")]
+use serde::{Deserialize, Serialize};
+
+/// State of device's power supply.
+///
+/// Battery level is reported as integer between 0 and 100.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum PowerInfo {
+    /// Failed to determine power status.
+    Unknown,
+    /// Device doesn't have battery.
+    Wired,
+    /// Device is running on the battery.
+    Discharging(u8),
+    /// Battery is charging.
+    Charging(u8),
+    /// Battery is charged.
+    Charged,
+}
@@ -3,6 +3,7 @@ use uuid::Uuid;
 
 fn main() {
     let gilrs = Gilrs::new().unwrap();
+    println!("Power info interval: {:?}\n", gilrs.power_info_interval());
     for (id, gamepad) in gilrs.gamepads() {
         println!(
             r#"Gamepad {id} ({name}):
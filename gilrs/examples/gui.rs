@@ -148,6 +148,12 @@ impl eframe::App for MyEguiApp {
                                         ui.end_row();
                                     }
 
+                                    if let Some(version) = gamepad.version() {
+                                        ui.label("Version");
+                                        ui.label(format!("{version:04x}"));
+                                        ui.end_row();
+                                    }
+
                                     ui.label("Gilrs ID");
                                     ui.label(gamepad.id().to_string());
                                     ui.end_row();
@@ -179,6 +185,7 @@ impl eframe::App for MyEguiApp {
                                         PowerInfo::Discharging(p) => format!("Discharging {p}"),
                                         PowerInfo::Charging(p) => format!("Charging {p}"),
                                         PowerInfo::Charged => "Charged".to_string(),
+                                        _ => "Unknown".to_string(),
                                     });
                                     ui.end_row();
                                 });
@@ -0,0 +1,16 @@
+// Copyright 2016-2018 Mateusz Sieczko and other GilRs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Custom input sources.
+//!
+//! Implement [`GamepadBackend`] and pass it to
+//! [`GilrsBuilder::with_backend()`](crate::GilrsBuilder::with_backend) to feed `Gilrs` from
+//! something else than native platform. Events produced by backend go through the same mapping,
+//! filters and state cache as native ones.
+
+pub use gilrs_core::backend::*;
+pub use gilrs_core::{native_ev_codes, AxisInfo, EvCode, Event, EventType};
@@ -0,0 +1,298 @@
+// Copyright 2016-2018 Mateusz Sieczko and other GilRs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::ev::{Axis, Button};
+use crate::utils;
+
+#[cfg(feature = "serde-serialize")]
+use serde::{Deserialize, Serialize};
+
+use std::collections::{HashMap, HashSet};
+
+/// Range of analog trigger values that is stretched to full `[0.0, 1.0]` range.
+///
+/// Worn or cheap triggers often can't be pulled all the way (or don't fully return), so their
+/// value never reaches 1.0 (or 0.0). Calibration can be captured with
+/// [`Gilrs::calibrate_trigger()`](crate::Gilrs::calibrate_trigger) and restored with
+/// [`Gilrs::set_trigger_calibration()`](crate::Gilrs::set_trigger_calibration). If
+/// `serde-serialize` feature is enabled, it can be stored together with other per-gamepad
+/// settings. Deserialization fails for values that [`new()`](TriggerCalibration::new) rejects.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-serialize", serde(try_from = "RawTriggerCalibration"))]
+pub struct TriggerCalibration {
+    min: f32,
+    max: f32,
+}
+
+/// Unchecked `TriggerCalibration`, deserialized before it's validated.
+#[cfg(feature = "serde-serialize")]
+#[derive(Deserialize)]
+struct RawTriggerCalibration {
+    min: f32,
+    max: f32,
+}
+
+#[cfg(feature = "serde-serialize")]
+impl TryFrom<RawTriggerCalibration> for TriggerCalibration {
+    type Error = &'static str;
+
+    fn try_from(raw: RawTriggerCalibration) -> Result<Self, Self::Error> {
+        TriggerCalibration::new(raw.min, raw.max).ok_or("invalid trigger calibration range")
+    }
+}
+
+impl TriggerCalibration {
+    /// Smallest `max - min` that is accepted by [`new()`](TriggerCalibration::new).
+    pub const MIN_RANGE: f32 = 0.1;
+
+    /// Creates calibration that maps `min` to 0.0 and `max` to 1.0.
+    ///
+    /// Returns `None` if `min` or `max` is outside of `[0.0, 1.0]` or if `max - min` is smaller
+    /// than [`MIN_RANGE`](TriggerCalibration::MIN_RANGE).
+    pub fn new(min: f32, max: f32) -> Option<Self> {
+        if (0.0..=1.0).contains(&min) && (0.0..=1.0).contains(&max) && max - min >= Self::MIN_RANGE
+        {
+            Some(TriggerCalibration { min, max })
+        } else {
+            None
+        }
+    }
+
+    /// Returns value that is mapped to 0.0.
+    pub fn min(&self) -> f32 {
+        self.min
+    }
+
+    /// Returns value that is mapped to 1.0.
+    pub fn max(&self) -> f32 {
+        self.max
+    }
+
+    /// Rescales uncalibrated trigger value.
+    pub fn apply(&self, val: f32) -> f32 {
+        utils::clamp_finite((val - self.min) / (self.max - self.min), 0.0, 1.0)
+    }
+}
+
+impl Default for TriggerCalibration {
+    fn default() -> Self {
+        TriggerCalibration { min: 0.0, max: 1.0 }
+    }
+}
+
+/// How axis value is changed before it's reported.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub enum ResponseCurve {
+    /// Value is not changed.
+    #[default]
+    Linear,
+    /// Absolute value is raised to given power, sign is kept. Exponent greater than 1.0 gives
+    /// more precision near the center, smaller than 1.0 makes small movements more pronounced.
+    /// Exponents that are not finite or not greater than 0.0 work like `Linear`.
+    Power(f32),
+}
+
+impl ResponseCurve {
+    /// Applies curve to value in `[-1.0, 1.0]` range.
+    pub fn apply(&self, val: f32) -> f32 {
+        match *self {
+            ResponseCurve::Power(exp) if exp.is_finite() && exp > 0.0 => {
+                utils::clamp_finite(val.abs().powf(exp).copysign(val), -1.0, 1.0)
+            }
+            ResponseCurve::Linear | ResponseCurve::Power(_) => val,
+        }
+    }
+}
+
+/// Per-gamepad tuning of axes and triggers that can be saved and applied again later.
+///
+/// Apply it with [`Gilrs::apply_profile()`](crate::Gilrs::apply_profile) and read current one
+/// with [`Gamepad::current_profile()`](crate::Gamepad::current_profile). Profile is kept when
+/// gamepad reconnects with the same ID. If `serde-serialize` feature is enabled, it can be stored,
+/// for example together with [`Gamepad::uuid()`](crate::Gamepad::uuid), and applied when the
+/// gamepad is connected again.
+///
+/// Inversion and response curve are applied to axis values before event filters, in that order.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct CalibrationProfile {
+    /// Dead zones used instead of ones reported by the device.
+    pub deadzones: HashMap<Axis, f32>,
+    /// Axes which values are negated.
+    pub inverted: HashSet<Axis>,
+    /// Response curves of axes. Axes that aren't listed are linear.
+    pub response_curves: HashMap<Axis, ResponseCurve>,
+    /// Calibration of left and right analog trigger.
+    pub triggers: [TriggerCalibration; 2],
+    /// Dead zones of left and right analog trigger used instead of ones reported by the device.
+    pub trigger_deadzones: [Option<f32>; 2],
+    /// `(pressed, released)` thresholds of buttons mapped to analog axes, used instead of ones
+    /// set with [`GilrsBuilder::set_axis_to_btn()`](crate::GilrsBuilder::set_axis_to_btn).
+    /// Thresholds where `pressed ≤ released` or values outside [0.0, 1.0] are ignored.
+    pub press_thresholds: HashMap<Button, (f32, f32)>,
+}
+
+impl CalibrationProfile {
+    /// Applies inversion and response curve of `axis` to its value.
+    pub(crate) fn apply(&self, axis: Axis, val: f32) -> f32 {
+        let val = if self.inverted.contains(&axis) {
+            -val
+        } else {
+            val
+        };
+
+        match self.response_curves.get(&axis) {
+            Some(curve) => curve.apply(val),
+            None => val,
+        }
+    }
+
+    /// Returns `(pressed, released)` threshold of `btn`, or `default` if profile doesn't have
+    /// valid one.
+    pub(crate) fn press_threshold(&self, btn: Button, default: (f32, f32)) -> (f32, f32) {
+        match self.press_thresholds.get(&btn) {
+            Some(&(pressed, released))
+                if pressed > released && released >= 0.0 && pressed <= 1.0 =>
+            {
+                (pressed, released)
+            }
+            _ => default,
+        }
+    }
+}
+
+/// Extremes of uncalibrated trigger values seen since last reset.
+#[derive(Copy, Clone, Debug, Default)]
+pub(crate) struct ObservedRange(Option<(f32, f32)>);
+
+impl ObservedRange {
+    pub(crate) fn observe(&mut self, val: f32) {
+        self.0 = match self.0 {
+            Some((min, max)) => Some((min.min(val), max.max(val))),
+            None => Some((val, val)),
+        };
+    }
+
+    pub(crate) fn to_calibration(self) -> Option<TriggerCalibration> {
+        self.0
+            .and_then(|(min, max)| TriggerCalibration::new(min, max))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CalibrationProfile, ObservedRange, ResponseCurve, TriggerCalibration};
+    use crate::ev::{Axis, Button};
+
+    #[test]
+    fn trigger_calibration_rescales() {
+        let cal = TriggerCalibration::new(0.1, 0.8).unwrap();
+        assert_eq!(cal.apply(0.05), 0.0);
+        assert_eq!(cal.apply(0.1), 0.0);
+        assert_eq!(cal.apply(0.8), 1.0);
+        assert_eq!(cal.apply(0.9), 1.0);
+        assert!((cal.apply(0.45) - 0.5).abs() < 1e-6);
+
+        let default = TriggerCalibration::default();
+        assert_eq!(default.apply(0.3), 0.3);
+    }
+
+    #[test]
+    fn trigger_calibration_rejects_bad_range() {
+        assert!(TriggerCalibration::new(0.5, 0.5).is_none());
+        assert!(TriggerCalibration::new(0.8, 0.2).is_none());
+        assert!(TriggerCalibration::new(-0.1, 0.9).is_none());
+        assert!(TriggerCalibration::new(0.0, 1.1).is_none());
+    }
+
+    #[test]
+    fn trigger_calibration_nan() {
+        assert_eq!(TriggerCalibration::default().apply(f32::NAN), 0.0);
+    }
+
+    #[cfg(feature = "serde-serialize")]
+    #[test]
+    fn trigger_calibration_deserialize_validates() {
+        use serde::de::value::{Error, MapDeserializer};
+        use serde::Deserialize;
+
+        let de = |min: f32, max: f32| {
+            let map = MapDeserializer::<_, Error>::new([("min", min), ("max", max)].into_iter());
+            TriggerCalibration::deserialize(map)
+        };
+
+        assert_eq!(de(0.1, 0.8).ok(), TriggerCalibration::new(0.1, 0.8));
+        assert!(de(0.8, 0.2).is_err());
+        assert!(de(0.5, 0.5).is_err());
+        assert!(de(f32::NAN, 1.0).is_err());
+    }
+
+    #[test]
+    fn profile_inverts_and_curves() {
+        let mut profile = CalibrationProfile::default();
+        assert_eq!(profile.apply(Axis::LeftStickX, 0.5), 0.5);
+
+        profile.inverted.insert(Axis::LeftStickY);
+        profile
+            .response_curves
+            .insert(Axis::LeftStickY, ResponseCurve::Power(2.0));
+        assert_eq!(profile.apply(Axis::LeftStickY, 0.5), -0.25);
+        assert_eq!(profile.apply(Axis::LeftStickY, -1.0), 1.0);
+        assert_eq!(profile.apply(Axis::LeftStickX, 0.5), 0.5);
+
+        assert_eq!(ResponseCurve::Power(0.5).apply(-0.25), -0.5);
+        assert_eq!(ResponseCurve::Linear.apply(-0.25), -0.25);
+    }
+
+    #[test]
+    fn invalid_power_curve_is_linear() {
+        for exp in [0.0, -2.0, f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            let curve = ResponseCurve::Power(exp);
+            for val in [-1.0, -0.25, 0.0, 0.5, 1.0] {
+                assert_eq!(curve.apply(val), val, "{:?} {}", curve, val);
+            }
+        }
+        // Tiny exponent still gives finite values.
+        assert_eq!(ResponseCurve::Power(f32::MIN_POSITIVE).apply(0.0), 0.0);
+        assert!(ResponseCurve::Power(f32::MIN_POSITIVE)
+            .apply(-0.5)
+            .is_finite());
+    }
+
+    #[test]
+    fn press_thresholds() {
+        let mut profile = CalibrationProfile::default();
+        let default = (0.75, 0.65);
+        assert_eq!(profile.press_threshold(Button::South, default), default);
+
+        profile.press_thresholds.insert(Button::South, (0.3, 0.2));
+        profile.press_thresholds.insert(Button::East, (0.2, 0.3));
+        profile.press_thresholds.insert(Button::West, (1.5, 0.2));
+        assert_eq!(profile.press_threshold(Button::South, default), (0.3, 0.2));
+        assert_eq!(profile.press_threshold(Button::East, default), default);
+        assert_eq!(profile.press_threshold(Button::West, default), default);
+        assert_eq!(profile.press_threshold(Button::North, default), default);
+    }
+
+    #[test]
+    fn observed_range() {
+        let mut range = ObservedRange::default();
+        assert!(range.to_calibration().is_none());
+
+        range.observe(0.02);
+        assert!(range.to_calibration().is_none());
+
+        range.observe(0.7);
+        range.observe(0.4);
+        assert_eq!(
+            range.to_calibration(),
+            Some(TriggerCalibration::new(0.02, 0.7).unwrap())
+        );
+    }
+}
@@ -0,0 +1,217 @@
+// Copyright 2016-2018 Mateusz Sieczko and other GilRs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+use crate::ev::{Button, Event, EventType};
+use crate::GamepadId;
+
+/// Detects button combinations like "Mode + East".
+///
+/// Chord is detected when all its buttons are held at the same time and were pressed within
+/// `window` from each other. It's detected once, then at least one of its buttons has to be
+/// released before it can be detected again. Every gamepad is tracked separately.
+///
+/// ```
+/// use std::time::Duration;
+/// use gilrs::ev::ChordDetector;
+/// use gilrs::{Button, Gilrs};
+///
+/// let mut gilrs = Gilrs::new().unwrap();
+/// let mut overlay = ChordDetector::new(&[Button::Mode, Button::East], Duration::from_millis(500))
+///     .with_callback(|id| println!("Toggle overlay for {}", id));
+///
+/// while let Some(event) = gilrs.next_event() {
+///     overlay.update(&event);
+/// }
+/// ```
+pub struct ChordDetector {
+    buttons: Vec<Button>,
+    window: Duration,
+    gamepads: Vec<ChordState>,
+    callback: Option<Box<dyn FnMut(GamepadId)>>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct ChordState {
+    // Time of press for each button of chord, `None` if it's not held.
+    pressed: Vec<Option<SystemTime>>,
+    fired: bool,
+}
+
+impl ChordDetector {
+    /// Creates detector for chord made of `buttons`. Duplicated and `Unknown` buttons are ignored.
+    pub fn new(buttons: &[Button], window: Duration) -> Self {
+        let mut chord: Vec<Button> = Vec::with_capacity(buttons.len());
+        for &btn in buttons {
+            if btn != Button::Unknown && !chord.contains(&btn) {
+                chord.push(btn);
+            }
+        }
+
+        ChordDetector {
+            buttons: chord,
+            window,
+            gamepads: Vec::new(),
+            callback: None,
+        }
+    }
+
+    /// Sets function that is called with gamepad's ID every time chord is detected.
+    pub fn with_callback<F: FnMut(GamepadId) + 'static>(mut self, callback: F) -> Self {
+        self.callback = Some(Box::new(callback));
+
+        self
+    }
+
+    /// Returns buttons that make up the chord.
+    pub fn buttons(&self) -> &[Button] {
+        &self.buttons
+    }
+
+    /// Processes event. Returns `true` (and calls callback) if this event completed the chord.
+    pub fn update(&mut self, event: &Event) -> bool {
+        let (btn, is_pressed) = match event.event {
+            EventType::ButtonPressed(btn, _) => (btn, true),
+            EventType::ButtonReleased(btn, _) => (btn, false),
+            EventType::Disconnected => {
+                if let Some(state) = self.gamepads.get_mut(usize::from(event.id)) {
+                    *state = ChordState::default();
+                }
+                return false;
+            }
+            _ => return false,
+        };
+
+        let i = match self.buttons.iter().position(|&b| b == btn) {
+            Some(i) => i,
+            None => return false,
+        };
+
+        let idx = usize::from(event.id);
+        if idx >= self.gamepads.len() {
+            self.gamepads.resize(idx + 1, ChordState::default());
+        }
+        let state = &mut self.gamepads[idx];
+        if state.pressed.len() != self.buttons.len() {
+            state.pressed = vec![None; self.buttons.len()];
+        }
+
+        if !is_pressed {
+            state.pressed[i] = None;
+            state.fired = false;
+            return false;
+        }
+
+        if state.pressed[i].is_none() {
+            state.pressed[i] = Some(event.time);
+        }
+
+        if state.fired {
+            return false;
+        }
+
+        let mut first = event.time;
+        let mut last = event.time;
+        for time in &state.pressed {
+            match *time {
+                Some(time) => {
+                    first = first.min(time);
+                    last = last.max(time);
+                }
+                None => return false,
+            }
+        }
+
+        if last.duration_since(first).unwrap_or_default() > self.window {
+            return false;
+        }
+
+        state.fired = true;
+        if let Some(callback) = self.callback.as_mut() {
+            callback(event.id);
+        }
+
+        true
+    }
+}
+
+impl fmt::Debug for ChordDetector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChordDetector")
+            .field("buttons", &self.buttons)
+            .field("window", &self.window)
+            .field("gamepads", &self.gamepads)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChordDetector;
+    use crate::ev::{Button, Code, Event, EventType};
+    use crate::GamepadId;
+    use gilrs_core::native_ev_codes as necs;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use std::time::{Duration, SystemTime};
+
+    fn event(id: usize, btn: Button, pressed: bool, ms: u64) -> Event {
+        let code = Code(necs::BTN_SOUTH);
+        Event {
+            id: GamepadId(id),
+            event: if pressed {
+                EventType::ButtonPressed(btn, code)
+            } else {
+                EventType::ButtonReleased(btn, code)
+            },
+            time: SystemTime::UNIX_EPOCH + Duration::from_millis(ms),
+        }
+    }
+
+    #[test]
+    fn chord_fires_once() {
+        let count = Rc::new(Cell::new(0));
+        let counter = count.clone();
+        let mut chord =
+            ChordDetector::new(&[Button::Mode, Button::East], Duration::from_millis(300))
+                .with_callback(move |_| counter.set(counter.get() + 1));
+
+        assert!(!chord.update(&event(0, Button::Mode, true, 0)));
+        assert!(!chord.update(&event(0, Button::South, true, 50)));
+        assert!(chord.update(&event(0, Button::East, true, 100)));
+        // Still held, must not fire again.
+        assert!(!chord.update(&event(0, Button::East, true, 150)));
+
+        // Releasing one button and pressing it again fires again.
+        assert!(!chord.update(&event(0, Button::East, false, 200)));
+        assert!(chord.update(&event(0, Button::East, true, 250)));
+
+        assert_eq!(count.get(), 2);
+    }
+
+    #[test]
+    fn chord_respects_window_and_gamepads() {
+        let mut chord =
+            ChordDetector::new(&[Button::Mode, Button::East], Duration::from_millis(300));
+
+        // Too slow.
+        chord.update(&event(0, Button::Mode, true, 0));
+        assert!(!chord.update(&event(0, Button::East, true, 1000)));
+
+        // Buttons on different gamepads.
+        chord.update(&event(1, Button::Mode, true, 2000));
+        assert!(!chord.update(&event(2, Button::East, true, 2010)));
+
+        // East is still held since 1000, so Mode has to be pressed close to it.
+        chord.update(&event(0, Button::Mode, false, 3000));
+        assert!(!chord.update(&event(0, Button::Mode, true, 3100)));
+        chord.update(&event(0, Button::East, false, 3200));
+        assert!(chord.update(&event(0, Button::East, true, 3300)));
+    }
+}
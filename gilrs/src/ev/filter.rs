@@ -16,6 +16,11 @@
 //! can also create them with default values using `new()` method. If filter is not configurable,
 //! it is implemented as function (for example `deadzone()`).
 //!
+//! By default, `Gilrs` already applies `DpadDebounce`, `axis_dpad_to_button`, `Jitter` and
+//! `deadzone` filters.
+//! See [`GilrsBuilder::with_default_filters()`](crate::GilrsBuilder::with_default_filters) for
+//! details and how to disable them.
+//!
 //! # Example
 //!
 //! ```
@@ -73,10 +78,11 @@
 //! `FilterFn` is also implemented for all `Fn(Option<Event>, &Gilrs) -> Option<Event>`, so above
 //! example could be simplified to passing closure to `filter()` function.
 
-use crate::ev::{Axis, AxisOrBtn, Button, Code, Event, EventType};
+use crate::ev::{Axis, AxisOrBtn, Button, Code, Event, EventType, Side};
 use crate::gamepad::{Gamepad, Gilrs};
 use crate::utils;
 
+use std::collections::VecDeque;
 use std::time::Duration;
 
 /// Discard axis events that changed less than `threshold`.
@@ -429,6 +435,461 @@ pub fn axis_dpad_to_button(ev: Option<Event>, gilrs: &mut Gilrs) -> Option<Event
     }
 }
 
+/// Debounces dpads reported as axes.
+///
+/// Some controllers shortly report centered dpad when it's rolled between directions, which
+/// makes `DPadX` and `DPadY` flicker between -1, 0 and 1. This filter holds changes to 0 for
+/// `window` and drops them if the axis leaves center again in the meantime. Other changes are
+/// passed immediately, so presses are never delayed.
+///
+/// Held change is returned when filter is called with `None` after `window` passes, so this
+/// filter should be first in chain. It's part of default filters, see
+/// [`GilrsBuilder::set_dpad_debounce()`](crate::GilrsBuilder::set_dpad_debounce).
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct DpadDebounce {
+    pub window: Duration,
+}
+
+impl DpadDebounce {
+    /// Creates new `DpadDebounce` filter with `window` set to 10ms.
+    pub fn new() -> Self {
+        DpadDebounce {
+            window: Duration::from_millis(10),
+        }
+    }
+}
+
+impl Default for DpadDebounce {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// State of `DpadDebounce` for one dpad axis.
+#[derive(Copy, Clone, Debug, Default)]
+pub(crate) struct DebounceState {
+    value: f32,
+    pending: Option<Event>,
+}
+
+impl DebounceState {
+    pub(crate) fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+}
+
+impl FilterFn for DpadDebounce {
+    fn filter(&self, ev: Option<Event>, gilrs: &mut Gilrs) -> Option<Event> {
+        match ev {
+            Some(event) if self.window > Duration::ZERO => {
+                let (idx, val) = match event.event {
+                    EventType::AxisChanged(Axis::DPadX, val, _) => (0, val),
+                    EventType::AxisChanged(Axis::DPadY, val, _) => (1, val),
+                    EventType::Disconnected => {
+                        if let Some(data) = gilrs.gamepads_data.get_mut(event.id.0) {
+                            data.dpad_debounce = Default::default();
+                        }
+                        return ev;
+                    }
+                    _ => return ev,
+                };
+                let state = match gilrs.gamepads_data.get_mut(event.id.0) {
+                    Some(data) => &mut data.dpad_debounce[idx],
+                    None => return ev,
+                };
+
+                if val == 0.0 && state.value != 0.0 {
+                    state.pending = ev;
+                    Some(event.drop())
+                } else if state.pending.take().is_some() && val == state.value {
+                    // Back in the same direction, nothing changed for the user.
+                    Some(event.drop())
+                } else {
+                    state.value = val;
+                    ev
+                }
+            }
+            Some(_) => ev,
+            None => {
+                let now = utils::time_now();
+                for data in &mut gilrs.gamepads_data {
+                    for state in &mut data.dpad_debounce {
+                        match state.pending {
+                            Some(pending)
+                                if matches!(
+                                    now.duration_since(pending.time),
+                                    Ok(dur) if dur >= self.window
+                                ) =>
+                            {
+                                state.pending = None;
+                                state.value = 0.0;
+                                return Some(pending);
+                            }
+                            _ => (),
+                        }
+                    }
+                }
+                None
+            }
+        }
+    }
+}
+
+/// Emulates dpad with stick.
+///
+/// When stick on `side` is pushed past `press` in some direction, this filter emits
+/// `ButtonPressed` and `ButtonChanged` events for matching `DPad*` button. The button is released
+/// when stick goes back below `release`, so `press` should be greater than `release` to avoid
+/// chatter at the boundary. Stick events are passed through unchanged.
+///
+/// Emulated buttons are stored in gamepad state like the real ones when the events are passed to
+/// [`Gilrs::update()`](crate::Gilrs::update), so you can add the [`Repeat`] filter after this one
+/// to get auto-repeat. Use two instances if you want both sticks to control the dpad. Filter
+/// remembers which directions it pressed itself, so real dpad doesn't affect it.
+///
+/// ```
+/// use gilrs::{GilrsBuilder, Filter, Side};
+/// use gilrs::ev::filter::{Repeat, StickToDpad};
+///
+/// let mut gilrs = GilrsBuilder::new().set_update_state(false).build().unwrap();
+/// let stick_to_dpad = StickToDpad::new(Side::Left);
+/// let repeat = Repeat::new();
+///
+/// loop {
+///     while let Some(event) = gilrs
+///         .next_event()
+///         .filter_ev(&stick_to_dpad, &mut gilrs)
+///         .filter_ev(&repeat, &mut gilrs)
+///     {
+///         gilrs.update(&event);
+///         println!("{:?}", event);
+///     }
+///     # break;
+/// }
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct StickToDpad {
+    pub side: Side,
+    pub press: f32,
+    pub release: f32,
+}
+
+impl StickToDpad {
+    /// Creates new `StickToDpad` filter for stick on `side` with `press` set to 0.5 and `release`
+    /// set to 0.3.
+    pub fn new(side: Side) -> Self {
+        StickToDpad {
+            side,
+            press: 0.5,
+            release: 0.3,
+        }
+    }
+
+    /// Returns `Some(true)` if button should be pressed, `Some(false)` if it should be released
+    /// and `None` if it should stay as is. `val` is stick value in direction of the button.
+    fn transition(&self, val: f32, is_pressed: bool) -> Option<bool> {
+        if !is_pressed && val >= self.press {
+            Some(true)
+        } else if is_pressed && val <= self.release {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for StickToDpad {
+    fn default() -> Self {
+        Self::new(Side::Left)
+    }
+}
+
+impl FilterFn for StickToDpad {
+    fn filter(&self, ev: Option<Event>, gilrs: &mut Gilrs) -> Option<Event> {
+        use gilrs_core::native_ev_codes as necs;
+
+        let (x, y) = self.side.stick_axes();
+        let (axis, val) = match ev {
+            Some(Event {
+                event: EventType::AxisChanged(axis, val, _),
+                ..
+            }) if axis == x || axis == y => (axis, val),
+            Some(Event {
+                id,
+                event: EventType::Disconnected,
+                ..
+            }) => {
+                if let Some(data) = gilrs.gamepads_data.get_mut(id.0) {
+                    data.stick_dpad[self.side as usize] = Default::default();
+                }
+                return ev;
+            }
+            _ => return ev,
+        };
+        let ev = ev?;
+
+        // Indexes to `GamepadData::stick_dpad`.
+        let directions = if axis == x {
+            [
+                (0, Button::DPadRight, Code(necs::BTN_DPAD_RIGHT), val),
+                (1, Button::DPadLeft, Code(necs::BTN_DPAD_LEFT), -val),
+            ]
+        } else {
+            [
+                (2, Button::DPadUp, Code(necs::BTN_DPAD_UP), val),
+                (3, Button::DPadDown, Code(necs::BTN_DPAD_DOWN), -val),
+            ]
+        };
+
+        for (i, btn, code, val) in directions {
+            let pressed = match gilrs.gamepads_data.get_mut(ev.id.0) {
+                Some(data) => &mut data.stick_dpad[self.side as usize][i],
+                None => return Some(ev),
+            };
+            if let Some(press) = self.transition(val, *pressed) {
+                *pressed = press;
+                let event = if press {
+                    EventType::ButtonPressed(btn, code)
+                } else {
+                    EventType::ButtonReleased(btn, code)
+                };
+                let value = if press { 1.0 } else { 0.0 };
+
+                gilrs.insert_event(Event { event, ..ev });
+                gilrs.insert_event(Event {
+                    event: EventType::ButtonChanged(btn, value, code),
+                    ..ev
+                });
+            }
+        }
+
+        Some(ev)
+    }
+}
+
+/// How [`SocdCleaner`] resolves opposite dpad directions held at the same time.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum SocdMode {
+    /// Both directions are released.
+    #[default]
+    Neutral,
+    /// Direction pressed last wins. When it's released, the other one is pressed again.
+    LastWins,
+    /// Up wins over down. Left and right held together are neutral.
+    UpPriority,
+}
+
+/// Cleans simultaneous opposite cardinal directions (SOCD) of dpad.
+///
+/// Controllers with buttons instead of dpad (hitboxes) can report left and right or up and down
+/// at the same time. This filter changes `DPad*` button events so opposite directions are never
+/// pressed together, using rule chosen by `mode`. Dpads reported as axes are converted to button
+/// events by default filters, so they go through this filter too.
+///
+/// Every press and release is followed by `ButtonChanged` event with value 0.0 or 1.0, original
+/// `ButtonChanged` events of dpad are dropped. If one input results in more than one event,
+/// remaining events are returned before next events of the same gamepad, or when filter is called
+/// with `None`. They keep time of the input.
+///
+/// Filter can be disabled for single gamepad with
+/// [`Gilrs::set_socd_cleaning()`](crate::Gilrs::set_socd_cleaning).
+///
+/// `next_event()` updates gamepad state with events before they are filtered, so with automatic
+/// state updates the state would still have opposite directions pressed together. Disable them
+/// with [`GilrsBuilder::set_update_state(false)`](crate::GilrsBuilder::set_update_state) and
+/// update state with filtered events instead:
+///
+/// ```
+/// use gilrs::{GilrsBuilder, Filter};
+/// use gilrs::ev::filter::{SocdCleaner, SocdMode};
+///
+/// let mut gilrs = GilrsBuilder::new().set_update_state(false).build().unwrap();
+/// let socd = SocdCleaner { mode: SocdMode::LastWins };
+///
+/// loop {
+///     while let Some(event) = gilrs.next_event().filter_ev(&socd, &mut gilrs) {
+///         gilrs.update(&event);
+///         println!("{:?}", event);
+///     }
+///     # break;
+/// }
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct SocdCleaner {
+    pub mode: SocdMode,
+}
+
+impl SocdCleaner {
+    /// Creates new `SocdCleaner` filter with `mode` set to `SocdMode::Neutral`.
+    pub fn new() -> Self {
+        SocdCleaner {
+            mode: SocdMode::Neutral,
+        }
+    }
+
+    /// Returns which of two opposite directions should be pressed. Second direction of vertical
+    /// pair is up, `last` is index of direction pressed last.
+    fn resolve(&self, held: [bool; 2], last: usize, vertical: bool) -> [bool; 2] {
+        if held != [true, true] {
+            return held;
+        }
+
+        match self.mode {
+            SocdMode::Neutral => [false, false],
+            SocdMode::LastWins => [last == 0, last == 1],
+            SocdMode::UpPriority if vertical => [false, true],
+            SocdMode::UpPriority => [false, false],
+        }
+    }
+}
+
+// Pairs of opposite directions, used by `SocdCleaner`.
+const SOCD_BUTTONS: [Button; 4] = [
+    Button::DPadLeft,
+    Button::DPadRight,
+    Button::DPadDown,
+    Button::DPadUp,
+];
+
+/// State of `SocdCleaner` for one gamepad.
+#[derive(Clone, Debug)]
+pub(crate) struct SocdState {
+    pub(crate) enabled: bool,
+    // Indexed like `SOCD_BUTTONS`.
+    held: [bool; 4],
+    active: [bool; 4],
+    codes: [Code; 4],
+    // Index of last pressed direction in each pair.
+    last: [usize; 2],
+    queue: VecDeque<Event>,
+}
+
+impl SocdState {
+    /// Forgets held buttons, but keeps `enabled`.
+    pub(crate) fn reset(&mut self) {
+        *self = SocdState {
+            enabled: self.enabled,
+            ..Default::default()
+        };
+    }
+}
+
+impl Default for SocdState {
+    fn default() -> Self {
+        use gilrs_core::native_ev_codes as necs;
+
+        SocdState {
+            enabled: true,
+            held: [false; 4],
+            active: [false; 4],
+            codes: [
+                Code(necs::BTN_DPAD_LEFT),
+                Code(necs::BTN_DPAD_RIGHT),
+                Code(necs::BTN_DPAD_DOWN),
+                Code(necs::BTN_DPAD_UP),
+            ],
+            last: [0; 2],
+            queue: VecDeque::new(),
+        }
+    }
+}
+
+impl SocdCleaner {
+    /// Adds events that should be returned for `event` to `state.queue`.
+    fn clean(&self, event: Event, state: &mut SocdState) {
+        let (btn, code, pressed) = match event.event {
+            EventType::ButtonPressed(btn, code) => (btn, code, Some(true)),
+            EventType::ButtonReleased(btn, code) => (btn, code, Some(false)),
+            EventType::ButtonChanged(btn, _, code) => (btn, code, None),
+            EventType::Disconnected => {
+                // Events queued before disconnection still have to be returned.
+                let queue = std::mem::take(&mut state.queue);
+                state.reset();
+                state.queue = queue;
+                state.queue.push_back(event);
+                return;
+            }
+            _ => {
+                state.queue.push_back(event);
+                return;
+            }
+        };
+        let i = match SOCD_BUTTONS.iter().position(|&b| b == btn) {
+            Some(i) => i,
+            None => {
+                state.queue.push_back(event);
+                return;
+            }
+        };
+
+        if let Some(pressed) = pressed {
+            state.held[i] = pressed;
+            state.codes[i] = code;
+            if pressed {
+                state.last[i / 2] = i % 2;
+            }
+        }
+
+        if !state.enabled {
+            state.active = state.held;
+            state.queue.push_back(event);
+            return;
+        }
+
+        let pair = i / 2 * 2;
+        let wanted = self.resolve(
+            [state.held[pair], state.held[pair + 1]],
+            state.last[i / 2],
+            pair == 2,
+        );
+
+        // Releases first, so opposite directions are never pressed together.
+        for press in [false, true] {
+            for (j, &wanted) in (pair..pair + 2).zip(wanted.iter()) {
+                if wanted == press && state.active[j] != wanted {
+                    let (btn, code) = (SOCD_BUTTONS[j], state.codes[j]);
+                    let (out, value) = if press {
+                        (EventType::ButtonPressed(btn, code), 1.0)
+                    } else {
+                        (EventType::ButtonReleased(btn, code), 0.0)
+                    };
+                    state.queue.push_back(Event {
+                        event: out,
+                        ..event
+                    });
+                    state.queue.push_back(Event {
+                        event: EventType::ButtonChanged(btn, value, code),
+                        ..event
+                    });
+                    state.active[j] = wanted;
+                }
+            }
+        }
+    }
+}
+
+impl FilterFn for SocdCleaner {
+    fn filter(&self, ev: Option<Event>, gilrs: &mut Gilrs) -> Option<Event> {
+        let event = match ev {
+            Some(event) => event,
+            None => {
+                return gilrs
+                    .gamepads_data
+                    .iter_mut()
+                    .find_map(|data| data.socd.queue.pop_front());
+            }
+        };
+        let state = match gilrs.gamepads_data.get_mut(event.id.0) {
+            Some(data) => &mut data.socd,
+            None => return ev,
+        };
+
+        // Events left from earlier inputs go first.
+        self.clean(event, state);
+        Some(state.queue.pop_front().unwrap_or_else(|| event.drop()))
+    }
+}
+
 /// Repeats pressed keys.
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub struct Repeat {
@@ -545,3 +1006,53 @@ impl Filter for Event {
         e
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{SocdCleaner, SocdMode, StickToDpad};
+    use crate::Side;
+
+    #[test]
+    fn socd_resolve() {
+        let neutral = SocdCleaner::new();
+        let last_wins = SocdCleaner {
+            mode: SocdMode::LastWins,
+        };
+        let up_priority = SocdCleaner {
+            mode: SocdMode::UpPriority,
+        };
+
+        // Single direction is never changed.
+        for filter in [neutral, last_wins, up_priority] {
+            assert_eq!(filter.resolve([true, false], 0, false), [true, false]);
+            assert_eq!(filter.resolve([false, true], 1, true), [false, true]);
+            assert_eq!(filter.resolve([false, false], 1, true), [false, false]);
+        }
+
+        assert_eq!(neutral.resolve([true, true], 1, false), [false, false]);
+        assert_eq!(neutral.resolve([true, true], 0, true), [false, false]);
+
+        assert_eq!(last_wins.resolve([true, true], 1, false), [false, true]);
+        assert_eq!(last_wins.resolve([true, true], 0, true), [true, false]);
+
+        assert_eq!(up_priority.resolve([true, true], 0, true), [false, true]);
+        assert_eq!(up_priority.resolve([true, true], 1, true), [false, true]);
+        assert_eq!(up_priority.resolve([true, true], 1, false), [false, false]);
+    }
+
+    #[test]
+    fn stick_to_dpad_hysteresis() {
+        let filter = StickToDpad::new(Side::Left);
+
+        assert_eq!(filter.transition(0.4, false), None);
+        assert_eq!(filter.transition(0.5, false), Some(true));
+        assert_eq!(filter.transition(-0.9, false), None);
+
+        // Between thresholds the button keeps its state.
+        assert_eq!(filter.transition(0.4, true), None);
+        assert_eq!(filter.transition(0.31, true), None);
+        assert_eq!(filter.transition(0.3, true), Some(false));
+        assert_eq!(filter.transition(-0.5, true), Some(false));
+        assert_eq!(filter.transition(1.0, true), None);
+    }
+}
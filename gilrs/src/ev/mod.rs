@@ -7,6 +7,7 @@
 
 //! Gamepad state and other event related functionality.
 
+mod chord;
 pub mod filter;
 pub mod state;
 
@@ -15,7 +16,10 @@ use std::{
     time::SystemTime,
 };
 
-use crate::{constants::*, gamepad::GamepadId, utils};
+pub use self::chord::ChordDetector;
+pub use gilrs_types::{Axis, AxisOrBtn, Button, ParseElementError, Side};
+
+use crate::{gamepad::GamepadId, utils, PowerInfo};
 
 #[cfg(feature = "serde-serialize")]
 use serde::{Deserialize, Serialize};
@@ -43,6 +47,37 @@ impl Code {
     pub fn into_u32(&self) -> u32 {
         self.0.into_u32()
     }
+
+    /// Returns code that backend uses for `btn` when it knows gamepad's layout, or `None` for
+    /// `Button::Unknown`.
+    pub fn from_button(btn: Button) -> Option<Code> {
+        use gilrs_core::native_ev_codes as necs;
+
+        match btn {
+            Button::South => Some(necs::BTN_SOUTH),
+            Button::East => Some(necs::BTN_EAST),
+            Button::North => Some(necs::BTN_NORTH),
+            Button::West => Some(necs::BTN_WEST),
+            Button::C => Some(necs::BTN_C),
+            Button::Z => Some(necs::BTN_Z),
+            Button::LeftTrigger => Some(necs::BTN_LT),
+            Button::LeftTrigger2 => Some(necs::BTN_LT2),
+            Button::RightTrigger => Some(necs::BTN_RT),
+            Button::RightTrigger2 => Some(necs::BTN_RT2),
+            Button::Select => Some(necs::BTN_SELECT),
+            Button::Start => Some(necs::BTN_START),
+            Button::Mode => Some(necs::BTN_MODE),
+            Button::LeftThumb => Some(necs::BTN_LTHUMB),
+            Button::RightThumb => Some(necs::BTN_RTHUMB),
+            Button::DPadUp => Some(necs::BTN_DPAD_UP),
+            Button::DPadDown => Some(necs::BTN_DPAD_DOWN),
+            Button::DPadLeft => Some(necs::BTN_DPAD_LEFT),
+            Button::DPadRight => Some(necs::BTN_DPAD_RIGHT),
+            Button::Share => Some(necs::BTN_SHARE),
+            _ => None,
+        }
+        .map(Code)
+    }
 }
 
 /// Holds information about gamepad event.
@@ -82,6 +117,7 @@ impl Event {
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[non_exhaustive]
 /// Gamepad event.
 pub enum EventType {
     /// Some button on gamepad has been pressed.
@@ -97,168 +133,14 @@ pub enum EventType {
     /// Gamepad has been connected. If gamepad's UUID doesn't match one of disconnected gamepads,
     /// newly connected gamepad will get new ID.
     Connected,
-    /// Gamepad has been disconnected. Disconnected gamepad will not generate any new events.
+    /// Gamepad has been disconnected. Disconnected gamepad will not generate any new events. Use
+    /// [`Gamepad::disconnect_reason()`](crate::Gamepad::disconnect_reason) to check why.
     Disconnected,
     /// There was an `Event`, but it was dropped by one of filters. You should ignore it.
     Dropped,
     /// A force feedback effect has ran for its duration and stopped.
     ForceFeedbackEffectCompleted,
-}
-
-#[repr(u16)]
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
-#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
-/// Gamepad's elements which state can be represented by value from 0.0 to 1.0.
-///
-/// ![Controller layout](https://gilrs-project.gitlab.io/gilrs/img/controller.svg)
-pub enum Button {
-    // Action Pad
-    South = BTN_SOUTH,
-    East = BTN_EAST,
-    North = BTN_NORTH,
-    West = BTN_WEST,
-    C = BTN_C,
-    Z = BTN_Z,
-    // Triggers
-    LeftTrigger = BTN_LT,
-    LeftTrigger2 = BTN_LT2,
-    RightTrigger = BTN_RT,
-    RightTrigger2 = BTN_RT2,
-    // Menu Pad
-    Select = BTN_SELECT,
-    Start = BTN_START,
-    Mode = BTN_MODE,
-    // Sticks
-    LeftThumb = BTN_LTHUMB,
-    RightThumb = BTN_RTHUMB,
-    // D-Pad
-    DPadUp = BTN_DPAD_UP,
-    DPadDown = BTN_DPAD_DOWN,
-    DPadLeft = BTN_DPAD_LEFT,
-    DPadRight = BTN_DPAD_RIGHT,
-
-    #[default]
-    Unknown = BTN_UNKNOWN,
-}
-
-impl Button {
-    pub fn is_action(self) -> bool {
-        use crate::Button::*;
-        matches!(self, South | East | North | West | C | Z)
-    }
-
-    pub fn is_trigger(self) -> bool {
-        use crate::Button::*;
-        matches!(
-            self,
-            LeftTrigger | LeftTrigger2 | RightTrigger | RightTrigger2
-        )
-    }
-
-    pub fn is_menu(self) -> bool {
-        use crate::Button::*;
-        matches!(self, Select | Start | Mode)
-    }
-
-    pub fn is_stick(self) -> bool {
-        use crate::Button::*;
-        matches!(self, LeftThumb | RightThumb)
-    }
-
-    pub fn is_dpad(self) -> bool {
-        use crate::Button::*;
-        matches!(self, DPadUp | DPadDown | DPadLeft | DPadRight)
-    }
-
-    pub fn to_nec(self) -> Option<Code> {
-        use gilrs_core::native_ev_codes as necs;
-
-        match self {
-            Button::South => Some(necs::BTN_SOUTH),
-            Button::East => Some(necs::BTN_EAST),
-            Button::North => Some(necs::BTN_NORTH),
-            Button::West => Some(necs::BTN_WEST),
-            Button::C => Some(necs::BTN_C),
-            Button::Z => Some(necs::BTN_Z),
-            Button::LeftTrigger => Some(necs::BTN_LT),
-            Button::LeftTrigger2 => Some(necs::BTN_LT2),
-            Button::RightTrigger => Some(necs::BTN_RT),
-            Button::RightTrigger2 => Some(necs::BTN_RT2),
-            Button::Select => Some(necs::BTN_SELECT),
-            Button::Start => Some(necs::BTN_START),
-            Button::Mode => Some(necs::BTN_MODE),
-            Button::LeftThumb => Some(necs::BTN_LTHUMB),
-            Button::RightThumb => Some(necs::BTN_RTHUMB),
-            Button::DPadUp => Some(necs::BTN_DPAD_UP),
-            Button::DPadDown => Some(necs::BTN_DPAD_DOWN),
-            Button::DPadLeft => Some(necs::BTN_DPAD_LEFT),
-            Button::DPadRight => Some(necs::BTN_DPAD_RIGHT),
-            _ => None,
-        }
-        .map(Code)
-    }
-}
-
-#[repr(u16)]
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
-#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
-/// Gamepad's elements which state can be represented by value from -1.0 to 1.0.
-///
-/// ![Controller layout](https://gilrs-project.gitlab.io/gilrs/img/controller.svg)
-pub enum Axis {
-    LeftStickX = AXIS_LSTICKX,
-    LeftStickY = AXIS_LSTICKY,
-    LeftZ = AXIS_LEFTZ,
-    RightStickX = AXIS_RSTICKX,
-    RightStickY = AXIS_RSTICKY,
-    RightZ = AXIS_RIGHTZ,
-    DPadX = AXIS_DPADX,
-    DPadY = AXIS_DPADY,
-    Unknown = AXIS_UNKNOWN,
-}
-
-impl Axis {
-    /// Returns true if axis is `LeftStickX`, `LeftStickY`, `RightStickX` or `RightStickY`.
-    pub fn is_stick(self) -> bool {
-        use crate::Axis::*;
-        matches!(self, LeftStickX | LeftStickY | RightStickX | RightStickY)
-    }
-
-    /// Returns the other axis from same element of gamepad, if any.
-    ///
-    /// | input       | output            |
-    /// |-------------|-------------------|
-    /// |`LeftStickX` |`Some(LeftStickY)` |
-    /// |`LeftStickY` |`Some(LeftStickX)` |
-    /// |`RightStickX`|`Some(RightStickY)`|
-    /// |`RightStickY`|`Some(RightStickX)`|
-    /// |`DpadX`      |`Some(DpadY)`      |
-    /// |`DpadY`      |`Some(DpadX)`      |
-    /// | …           |`None`             |
-    pub fn second_axis(self) -> Option<Self> {
-        use crate::Axis::*;
-        match self {
-            LeftStickX => Some(LeftStickY),
-            LeftStickY => Some(LeftStickX),
-            RightStickX => Some(RightStickY),
-            RightStickY => Some(RightStickX),
-            DPadX => Some(DPadY),
-            DPadY => Some(DPadX),
-            _ => None,
-        }
-    }
-}
-
-/// Represents `Axis` or `Button`.
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
-#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
-pub enum AxisOrBtn {
-    Axis(Axis),
-    Btn(Button),
-}
-
-impl AxisOrBtn {
-    pub(crate) fn is_button(&self) -> bool {
-        matches!(self, AxisOrBtn::Btn(_))
-    }
+    /// Power supply state of gamepad has changed. See
+    /// [`GilrsBuilder::set_power_info_interval()`](crate::GilrsBuilder::set_power_info_interval).
+    PowerChanged(PowerInfo),
 }
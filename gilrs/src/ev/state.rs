@@ -20,6 +20,7 @@ pub struct GamepadState {
     buttons: FnvHashMap<Code, ButtonData>,
     // Indexed by EvCode (nec)
     axes: FnvHashMap<Code, AxisData>,
+    last_input: Option<SystemTime>,
 }
 
 impl GamepadState {
@@ -27,6 +28,7 @@ impl GamepadState {
         GamepadState {
             buttons: FnvHashMap::default(),
             axes: FnvHashMap::default(),
+            last_input: None,
         }
     }
 
@@ -39,6 +41,24 @@ impl GamepadState {
             .unwrap_or(false)
     }
 
+    /// Returns `true` if given button is pressed, but wasn't pressed when
+    /// [`Gilrs::inc()`](crate::Gilrs::inc) was last called.
+    pub fn just_pressed(&self, btn: Code) -> bool {
+        self.buttons
+            .get(&btn)
+            .map(|s| s.is_pressed && !s.was_pressed)
+            .unwrap_or(false)
+    }
+
+    /// Returns `true` if given button is not pressed, but was pressed when
+    /// [`Gilrs::inc()`](crate::Gilrs::inc) was last called.
+    pub fn just_released(&self, btn: Code) -> bool {
+        self.buttons
+            .get(&btn)
+            .map(|s| !s.is_pressed && s.was_pressed)
+            .unwrap_or(false)
+    }
+
     /// Returns value of `el` or 0.0 when there is no information about it. `el` can be either axis
     /// or button.
     pub fn value(&self, el: Code) -> f32 {
@@ -69,6 +89,18 @@ impl GamepadState {
         self.axes.get(&axis)
     }
 
+    /// Returns time of the most recent button or axis change, or `None` if there was none.
+    /// Repeated button events are not counted as input.
+    pub fn last_input_time(&self) -> Option<SystemTime> {
+        self.last_input
+    }
+
+    fn record_input(&mut self, timestamp: SystemTime) {
+        if self.last_input.map_or(true, |last| last < timestamp) {
+            self.last_input = Some(timestamp);
+        }
+    }
+
     pub(crate) fn set_btn_pressed(
         &mut self,
         btn: Code,
@@ -89,6 +121,7 @@ impl GamepadState {
         data.is_repeating = false;
         data.counter = counter;
         data.last_event_ts = timestamp;
+        self.record_input(timestamp);
     }
 
     pub(crate) fn set_btn_repeating(&mut self, btn: Code, counter: u64, timestamp: SystemTime) {
@@ -115,11 +148,44 @@ impl GamepadState {
         data.value = value;
         data.counter = counter;
         data.last_event_ts = timestamp;
+        self.record_input(timestamp);
     }
 
-    pub(crate) fn update_axis(&mut self, axis: Code, data: AxisData) {
+    pub(crate) fn update_axis(&mut self, axis: Code, mut data: AxisData) {
+        self.record_input(data.timestamp());
+        if let Some(old) = self.axes.get(&axis) {
+            data.raw = old.raw;
+        }
         self.axes.insert(axis, data);
     }
+
+    pub(crate) fn set_axis_raw(&mut self, axis: Code, raw: i32) {
+        self.axes
+            .entry(axis)
+            .or_insert_with(|| AxisData::new(0.0, 0, SystemTime::UNIX_EPOCH))
+            .raw = raw;
+    }
+
+    pub(crate) fn set_btn_raw(&mut self, btn: Code, raw: i32) {
+        self.buttons
+            .entry(btn)
+            .or_insert_with(|| ButtonData::new(0.0, false, false, 0, SystemTime::UNIX_EPOCH))
+            .raw = raw;
+    }
+
+    /// Forgets state of all buttons and axes.
+    pub(crate) fn reset(&mut self) {
+        self.buttons.clear();
+        self.axes.clear();
+    }
+
+    /// Remembers which buttons are pressed, so `just_pressed()` and `just_released()` can compare
+    /// against it.
+    pub(crate) fn next_frame(&mut self) {
+        for data in self.buttons.values_mut() {
+            data.was_pressed = data.is_pressed;
+        }
+    }
 }
 
 /// Iterator over `ButtonData`.
@@ -152,6 +218,9 @@ pub struct ButtonData {
     value: f32,
     is_pressed: bool,
     is_repeating: bool,
+    // Value of `is_pressed` at the last frame boundary.
+    was_pressed: bool,
+    raw: i32,
 }
 
 impl ButtonData {
@@ -168,6 +237,8 @@ impl ButtonData {
             value,
             is_pressed: pressed,
             is_repeating: repeating,
+            was_pressed: false,
+            raw: 0,
         }
     }
 
@@ -186,6 +257,12 @@ impl ButtonData {
         self.is_repeating
     }
 
+    /// Returns last value reported by the device, before mapping, calibration and filters. Only
+    /// buttons reported as axes (for example analog triggers) have raw value, it's 0 otherwise.
+    pub fn raw_value(&self) -> i32 {
+        self.raw
+    }
+
     /// Returns value of counter when button state last changed.
     pub fn counter(&self) -> u64 {
         self.counter
@@ -203,6 +280,7 @@ pub struct AxisData {
     last_event_ts: SystemTime,
     last_event_c: u64,
     value: f32,
+    raw: i32,
 }
 
 impl AxisData {
@@ -211,6 +289,7 @@ impl AxisData {
             last_event_ts: time,
             last_event_c: counter,
             value,
+            raw: 0,
         }
     }
     /// Returns value of axis.
@@ -218,6 +297,13 @@ impl AxisData {
         self.value
     }
 
+    /// Returns last value reported by the device, before mapping, calibration and filters. Can
+    /// differ from `value()` even if there were no new events, for example when movement is
+    /// inside dead zone.
+    pub fn raw_value(&self) -> i32 {
+        self.raw
+    }
+
     /// Returns value of counter when axis value last changed.
     pub fn counter(&self) -> u64 {
         self.last_event_c
@@ -228,3 +314,85 @@ impl AxisData {
         self.last_event_ts
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::GamepadState;
+    use crate::ev::Code;
+    use crate::utils;
+
+    use gilrs_core::native_ev_codes as necs;
+    use std::time::Duration;
+
+    #[test]
+    fn just_pressed_across_frames() {
+        let btn = Code(necs::BTN_SOUTH);
+        let mut state = GamepadState::new();
+        let now = utils::time_now();
+
+        assert!(!state.just_pressed(btn));
+        assert!(!state.just_released(btn));
+
+        // Frame 1: press
+        state.set_btn_pressed(btn, true, 1, now);
+        assert!(state.is_pressed(btn));
+        assert!(state.just_pressed(btn));
+        assert!(!state.just_released(btn));
+        state.next_frame();
+
+        // Frame 2: hold
+        assert!(state.is_pressed(btn));
+        assert!(!state.just_pressed(btn));
+        assert!(!state.just_released(btn));
+        state.next_frame();
+
+        // Frame 3: release
+        state.set_btn_pressed(btn, false, 3, now);
+        assert!(!state.is_pressed(btn));
+        assert!(!state.just_pressed(btn));
+        assert!(state.just_released(btn));
+        state.next_frame();
+
+        // Frame 4: nothing
+        assert!(!state.just_pressed(btn));
+        assert!(!state.just_released(btn));
+    }
+
+    #[test]
+    fn press_and_release_in_one_frame() {
+        let btn = Code(necs::BTN_EAST);
+        let mut state = GamepadState::new();
+        let now = utils::time_now();
+
+        state.set_btn_pressed(btn, true, 1, now);
+        state.set_btn_pressed(btn, false, 1, now);
+        assert!(!state.just_pressed(btn));
+        assert!(!state.just_released(btn));
+
+        state.next_frame();
+        state.set_btn_pressed(btn, true, 2, now);
+        state.next_frame();
+        state.set_btn_pressed(btn, false, 3, now);
+        state.set_btn_pressed(btn, true, 3, now);
+        assert!(!state.just_pressed(btn));
+        assert!(!state.just_released(btn));
+    }
+
+    #[test]
+    fn last_input_time() {
+        let btn = Code(necs::BTN_SOUTH);
+        let mut state = GamepadState::new();
+        let now = utils::time_now();
+        assert_eq!(state.last_input_time(), None);
+
+        state.set_btn_pressed(btn, true, 1, now);
+        assert_eq!(state.last_input_time(), Some(now));
+
+        // Repeat doesn't count as input.
+        state.set_btn_repeating(btn, 2, now + Duration::from_secs(1));
+        assert_eq!(state.last_input_time(), Some(now));
+
+        state.set_btn_value(btn, 0.5, 3, now + Duration::from_secs(2));
+        assert_eq!(state.last_input_time(), Some(now + Duration::from_secs(2)));
+    }
+}
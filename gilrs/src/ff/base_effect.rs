@@ -7,6 +7,7 @@
 
 use std::ops::Mul;
 
+use super::effect_source::Magnitude;
 use super::time::Ticks;
 
 /// Kind of [`BaseEffect`](struct.BaseEffect.html).
@@ -16,27 +17,80 @@ use super::time::Ticks;
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 #[non_exhaustive]
 pub enum BaseEffectType {
-    Weak { magnitude: u16 },
-    Strong { magnitude: u16 },
+    Weak {
+        magnitude: u16,
+    },
+    Strong {
+        magnitude: u16,
+    },
+    /// Magnitude of weak motor changes linearly from `start` to `end` during `play_for` and
+    /// reaches `end` in its last tick.
+    WeakRamp {
+        start: u16,
+        end: u16,
+    },
+    /// Magnitude of strong motor changes linearly from `start` to `end` during `play_for` and
+    /// reaches `end` in its last tick.
+    StrongRamp {
+        start: u16,
+        end: u16,
+    },
 }
 
 impl BaseEffectType {
-    fn magnitude(&self) -> u16 {
+    /// Returns magnitude of motors `ticks` after playback started.
+    fn at(&self, ticks: Ticks, play_for: Ticks) -> Magnitude {
         match *self {
-            BaseEffectType::Weak { magnitude } => magnitude,
-            BaseEffectType::Strong { magnitude } => magnitude,
+            BaseEffectType::Weak { magnitude } => Magnitude {
+                strong: 0,
+                weak: magnitude,
+            },
+            BaseEffectType::Strong { magnitude } => Magnitude {
+                strong: magnitude,
+                weak: 0,
+            },
+            BaseEffectType::WeakRamp { start, end } => Magnitude {
+                strong: 0,
+                weak: ramp(start, end, ticks, play_for),
+            },
+            BaseEffectType::StrongRamp { start, end } => Magnitude {
+                strong: ramp(start, end, ticks, play_for),
+                weak: 0,
+            },
         }
     }
 }
 
+fn ramp(start: u16, end: u16, ticks: Ticks, play_for: Ticks) -> u16 {
+    let progress = if play_for.0 <= 1 {
+        1.0
+    } else {
+        (ticks.0 as f32 / (play_for.0 - 1) as f32).min(1.0)
+    };
+
+    (start as f32 + (end as f32 - start as f32) * progress).round() as u16
+}
+
 impl Mul<f32> for BaseEffectType {
     type Output = BaseEffectType;
 
     fn mul(self, rhs: f32) -> Self::Output {
-        let mg = (self.magnitude() as f32 * rhs) as u16;
+        let mul = |mg: u16| (mg as f32 * rhs) as u16;
         match self {
-            BaseEffectType::Weak { .. } => BaseEffectType::Weak { magnitude: mg },
-            BaseEffectType::Strong { .. } => BaseEffectType::Strong { magnitude: mg },
+            BaseEffectType::Weak { magnitude } => BaseEffectType::Weak {
+                magnitude: mul(magnitude),
+            },
+            BaseEffectType::Strong { magnitude } => BaseEffectType::Strong {
+                magnitude: mul(magnitude),
+            },
+            BaseEffectType::WeakRamp { start, end } => BaseEffectType::WeakRamp {
+                start: mul(start),
+                end: mul(end),
+            },
+            BaseEffectType::StrongRamp { start, end } => BaseEffectType::StrongRamp {
+                start: mul(start),
+                end: mul(end),
+            },
         }
     }
 }
@@ -63,14 +117,15 @@ pub struct BaseEffect {
 }
 
 impl BaseEffect {
-    /// Returns `Weak` or `Strong` after applying envelope.
-    pub(super) fn magnitude_at(&self, ticks: Ticks) -> BaseEffectType {
-        if let Some(wrapped) = self.scheduling.wrap(ticks) {
-            let att =
-                self.scheduling.at(wrapped) * self.envelope.at(wrapped, self.scheduling.play_for);
-            self.kind * att
-        } else {
-            self.kind * 0.0
+    /// Returns magnitude of motors after applying envelope.
+    pub(super) fn magnitude_at(&self, ticks: Ticks) -> Magnitude {
+        match self.scheduling.wrap(ticks) {
+            // Envelope is only defined during playback, not during delay.
+            Some(wrapped) if self.scheduling.at(wrapped) > 0.0 => {
+                let att = self.envelope.at(wrapped, self.scheduling.play_for);
+                self.kind.at(wrapped, self.scheduling.play_for) * att
+            }
+            _ => Magnitude::zero(),
         }
     }
 }
@@ -149,3 +204,44 @@ impl Default for Replay {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{BaseEffect, BaseEffectType, Replay};
+    use crate::ff::Ticks;
+
+    #[test]
+    fn ramp_reaches_end() {
+        let effect = BaseEffect {
+            kind: BaseEffectType::StrongRamp {
+                start: 1000,
+                end: 41_000,
+            },
+            scheduling: Replay {
+                after: Ticks(0),
+                play_for: Ticks(5),
+                with_delay: Ticks(5),
+            },
+            ..Default::default()
+        };
+
+        let strong: Vec<u16> = (0..11)
+            .map(|t| effect.magnitude_at(Ticks(t)).strong)
+            .collect();
+        assert_eq!(
+            strong,
+            [1000, 11_000, 21_000, 31_000, 41_000, 0, 0, 0, 0, 0, 1000]
+        );
+        assert!((0..11).all(|t| effect.magnitude_at(Ticks(t)).weak == 0));
+
+        let down = BaseEffect {
+            kind: BaseEffectType::WeakRamp {
+                start: 40_000,
+                end: 0,
+            },
+            ..effect
+        };
+        assert_eq!(down.magnitude_at(Ticks(0)).weak, 40_000);
+        assert_eq!(down.magnitude_at(Ticks(4)).weak, 0);
+    }
+}
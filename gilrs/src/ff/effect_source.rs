@@ -11,7 +11,7 @@ use std::{fmt, mem, u16};
 
 use crate::{Event, EventType, GamepadId};
 
-use super::base_effect::{BaseEffect, BaseEffectType};
+use super::base_effect::BaseEffect;
 use super::time::{Repeat, Ticks};
 
 use vec_map::VecMap;
@@ -234,6 +234,22 @@ impl fmt::Display for DistanceModelError {
     }
 }
 
+/// Specifies how output of effects playing on the same gamepad at the same time is combined.
+///
+/// Set with [`Gilrs::set_ff_mixing_policy()`](crate::Gilrs::set_ff_mixing_policy).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum MixingPolicy {
+    /// Magnitudes of all effects are added (saturating at `u16::MAX`).
+    Sum,
+    /// Each motor uses the strongest magnitude of all effects.
+    #[default]
+    Max,
+    /// Only effects with highest [priority](crate::ff::EffectBuilder::priority) of those that
+    /// currently produce any output are played, combined like with `Max`. Lower priority effects
+    /// keep running and are heard again when higher priority ones become silent.
+    PriorityOverride,
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub(super) enum EffectState {
     Playing { since: Ticks },
@@ -249,6 +265,11 @@ pub(crate) struct EffectSource {
     pub(super) distance_model: DistanceModel,
     pub(super) position: [f32; 3],
     pub(super) gain: f32,
+    pub(super) priority: u8,
+    // Gain that `gain` is ramped to and how much it can change in one tick.
+    target_gain: f32,
+    gain_step: f32,
+    gain_ramp: Ticks,
     pub(super) state: EffectState,
     pub(super) completion_events: Vec<Event>,
 }
@@ -261,6 +282,7 @@ impl EffectSource {
         dist_model: DistanceModel,
         position: [f32; 3],
         gain: f32,
+        gain_ramp: Ticks,
     ) -> Self {
         EffectSource {
             base_effects,
@@ -269,11 +291,40 @@ impl EffectSource {
             distance_model: dist_model,
             position,
             gain,
+            priority: 0,
+            target_gain: gain,
+            gain_step: 0.0,
+            gain_ramp,
             state: EffectState::Stopped,
             completion_events: vec![],
         }
     }
 
+    /// Changes gain immediately or, if `gain_ramp` is not zero, starts ramping to it.
+    pub(super) fn set_gain(&mut self, gain: f32) {
+        self.target_gain = gain;
+        if self.gain_ramp.0 == 0 {
+            self.gain = gain;
+        } else {
+            self.gain_step = (gain - self.gain).abs() / self.gain_ramp.0 as f32;
+        }
+    }
+
+    pub(super) fn set_gain_ramp(&mut self, ramp: Ticks) {
+        self.gain_ramp = ramp;
+        let target = self.target_gain;
+        self.set_gain(target);
+    }
+
+    /// Moves gain one tick closer to the target.
+    pub(super) fn advance_gain(&mut self) {
+        if self.gain < self.target_gain {
+            self.gain = (self.gain + self.gain_step).min(self.target_gain);
+        } else if self.gain > self.target_gain {
+            self.gain = (self.gain - self.gain_step).max(self.target_gain);
+        }
+    }
+
     pub(super) fn combine_base_effects(&mut self, ticks: Ticks, actor_pos: [f32; 3]) -> Magnitude {
         let ticks = match self.state {
             EffectState::Playing { since } => {
@@ -304,14 +355,7 @@ impl EffectSource {
 
         let mut final_magnitude = Magnitude::zero();
         for effect in &self.base_effects {
-            match effect.magnitude_at(ticks) {
-                BaseEffectType::Strong { magnitude } => {
-                    final_magnitude.strong = final_magnitude.strong.saturating_add(magnitude)
-                }
-                BaseEffectType::Weak { magnitude } => {
-                    final_magnitude.weak = final_magnitude.weak.saturating_add(magnitude)
-                }
-            };
+            final_magnitude += effect.magnitude_at(ticks);
         }
         final_magnitude * attenuation
     }
@@ -332,6 +376,18 @@ impl Magnitude {
     pub fn zero() -> Self {
         Magnitude { strong: 0, weak: 0 }
     }
+
+    pub fn is_zero(self) -> bool {
+        self.strong == 0 && self.weak == 0
+    }
+
+    /// Returns stronger value for each motor.
+    pub fn max(self, other: Magnitude) -> Self {
+        Magnitude {
+            strong: self.strong.max(other.strong),
+            weak: self.weak.max(other.weak),
+        }
+    }
 }
 
 impl Mul<f32> for Magnitude {
@@ -5,17 +5,17 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
-use super::effect_source::{DistanceModel, EffectSource, EffectState, Magnitude};
+use super::effect_source::{DistanceModel, EffectSource, EffectState, Magnitude, MixingPolicy};
 use super::time::{Repeat, Ticks, TICK_DURATION};
 
 use std::ops::{Deref, DerefMut};
-use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
 use std::thread;
 use std::time::{Duration, Instant};
 
 use crate::gamepad::GamepadId;
 use crate::Event;
-use gilrs_core::FfDevice;
+use gilrs_core::backend::BackendFfDevice;
 
 use vec_map::VecMap;
 
@@ -39,7 +39,7 @@ pub(crate) enum Message {
     },
     Open {
         id: usize,
-        device: FfDevice,
+        device: Box<dyn BackendFfDevice>,
     },
     Close {
         id: usize,
@@ -72,6 +72,33 @@ pub(crate) enum Message {
         id: usize,
         gain: f32,
     },
+    SetGainRamp {
+        id: usize,
+        ramp: Ticks,
+    },
+    SetPriority {
+        id: usize,
+        priority: u8,
+    },
+    SetMixingPolicy {
+        id: usize,
+        policy: MixingPolicy,
+    },
+    SetMotors {
+        id: usize,
+        strong: u16,
+        weak: u16,
+    },
+    SetEnabled {
+        id: usize,
+        enabled: bool,
+    },
+    Rumble {
+        id: usize,
+        strong: u16,
+        weak: u16,
+        duration: Ticks,
+    },
 }
 
 pub(crate) enum FfMessage {
@@ -88,12 +115,35 @@ impl Message {
             &SetListenerPosition { .. } | &HandleCloned { .. } | &HandleDropped { .. }
         )
     }
+
+    // Whether message changes output of motors and should be played without waiting for next
+    // tick.
+    fn is_immediate(&self) -> bool {
+        use self::Message::*;
+
+        matches!(
+            self,
+            &Play { .. }
+                | &Stop { .. }
+                | &SetMotors { .. }
+                | &SetEnabled { .. }
+                | &Rumble { .. }
+                | &SetMixingPolicy { .. }
+        )
+    }
 }
 
 #[derive(Debug)]
 struct Device {
-    inner: FfDevice,
+    inner: Box<dyn BackendFfDevice>,
     position: [f32; 3],
+    /// Raw motor speeds that override effects.
+    motors: Option<Magnitude>,
+    /// One-shot rumble added to effects and tick at which it ends.
+    rumble: Option<(Magnitude, Ticks)>,
+    /// Motors of disabled device are always stopped.
+    enabled: bool,
+    mixing: MixingPolicy,
 }
 
 struct Effect {
@@ -134,148 +184,248 @@ impl DerefMut for Effect {
     }
 }
 
-impl From<FfDevice> for Device {
-    fn from(inner: FfDevice) -> Self {
+impl From<Box<dyn BackendFfDevice>> for Device {
+    fn from(inner: Box<dyn BackendFfDevice>) -> Self {
         Device {
             inner,
             position: [0.0, 0.0, 0.0],
+            motors: None,
+            rumble: None,
+            enabled: true,
+            mixing: MixingPolicy::default(),
         }
     }
 }
 
+// Messages are handled as soon as they arrive, not once per tick. Messages that change output
+// (`Message::is_immediate()`) also replay current tick, so rumble starts within time needed to
+// wake up this thread (see module docs) instead of up to `TICK_DURATION`.
+// Replaying tick doesn't advance effects, so their timing is not affected.
 pub(crate) fn run(tx: Sender<FfMessage>, rx: Receiver<Message>) {
     let mut effects = VecMap::<Effect>::new();
     let mut devices = VecMap::<Device>::new();
     let sleep_dur = Duration::from_millis(TICK_DURATION.into());
     let mut tick = Ticks(0);
+    let mut next_tick = Instant::now();
     let mut completion_events = Vec::<Event>::new();
 
     loop {
-        let t1 = Instant::now();
-        while let Ok(ev) = rx.try_recv() {
-            if ev.use_trace_level() {
-                trace!("New ff event: {:?}", ev);
-            } else {
-                debug!("New ff event: {:?}", ev);
+        let timeout = next_tick.saturating_duration_since(Instant::now());
+        let ev = match rx.recv_timeout(timeout) {
+            Ok(ev) => Some(ev),
+            Err(RecvTimeoutError::Timeout) => None,
+            Err(RecvTimeoutError::Disconnected) => {
+                thread::sleep(timeout);
+                None
             }
+        };
 
-            match ev {
-                Message::Create { id, effect } => {
-                    effects.insert(id, (*effect).into());
-                }
-                Message::Play { id } => {
-                    if let Some(effect) = effects.get_mut(id) {
-                        effect.source.state = EffectState::Playing { since: tick }
-                    } else {
-                        error!("{:?} with wrong ID", ev);
-                    }
-                }
-                Message::Stop { id } => {
-                    if let Some(effect) = effects.get_mut(id) {
-                        effect.source.state = EffectState::Stopped
-                    } else {
-                        error!("{:?} with wrong ID", ev);
-                    }
-                }
-                Message::Open { id, device } => {
-                    devices.insert(id, device.into());
-                }
-                Message::Close { id } => {
-                    devices.remove(id);
-                }
-                Message::SetListenerPosition { id, position } => {
-                    if let Some(device) = devices.get_mut(id) {
-                        device.position = position;
-                    } else {
-                        error!("{:?} with wrong ID", ev);
-                    }
-                }
-                Message::HandleCloned { id } => {
-                    if let Some(effect) = effects.get_mut(id) {
-                        effect.inc();
-                    } else {
-                        error!("{:?} with wrong ID", ev);
-                    }
-                }
-                Message::HandleDropped { id } => {
-                    let mut drop = false;
-                    if let Some(effect) = effects.get_mut(id) {
-                        if effect.dec() == 0 {
-                            drop = true;
-                        }
-                    } else {
-                        error!("{:?} with wrong ID", ev);
-                    }
-
-                    if drop {
-                        effects.remove(id);
-                    }
-                }
-                Message::SetGamepads { id, gamepads } => {
-                    if let Some(eff) = effects.get_mut(id) {
-                        eff.source.devices = gamepads;
-                    } else {
-                        error!("Invalid effect id {} when changing gamepads.", id);
-                    }
-                }
-                Message::AddGamepad { id, gamepad_id } => {
-                    if let Some(eff) = effects.get_mut(id) {
-                        eff.source.devices.insert(gamepad_id.0, ());
-                    } else {
-                        error!("Invalid effect id {} when changing gamepads.", id);
-                    }
-                }
-                Message::SetRepeat { id, repeat } => {
-                    if let Some(eff) = effects.get_mut(id) {
-                        eff.source.repeat = repeat;
-                    } else {
-                        error!("Invalid effect id {} when changing repeat mode.", id);
-                    }
-                }
-                Message::SetDistanceModel { id, model } => {
-                    if let Some(eff) = effects.get_mut(id) {
-                        eff.source.distance_model = model;
-                    } else {
-                        error!("Invalid effect id {} when changing distance model.", id);
-                    }
-                }
-                Message::SetPosition { id, position } => {
-                    if let Some(eff) = effects.get_mut(id) {
-                        eff.source.position = position;
-                    } else {
-                        error!("Invalid effect id {}.", id);
-                    }
-                }
-                Message::SetGain { id, gain } => {
-                    if let Some(eff) = effects.get_mut(id) {
-                        eff.source.gain = gain;
-                    } else {
-                        error!("Invalid effect id {} when changing effect gain.", id);
-                    }
-                }
-            }
+        if let Some(ev) = ev {
+            handle_message(
+                ev,
+                &mut effects,
+                &mut devices,
+                tick,
+                &mut completion_events,
+                &tx,
+            );
+            continue;
         }
 
+        tick.inc();
+        for (_, effect) in effects.iter_mut() {
+            effect.source.advance_gain();
+        }
         combine_and_play(&mut effects, &mut devices, tick, &mut completion_events);
-        completion_events.iter().for_each(|ev| {
-            let _ = tx.send(FfMessage::EffectCompleted { event: *ev });
-        });
-        completion_events.clear();
+        send_completion_events(&tx, &mut completion_events);
 
-        let dur = Instant::now().duration_since(t1);
-        if dur > sleep_dur {
-            // TODO: Should we add dur - sleep_dur to next iteration's dur?
+        next_tick += sleep_dur;
+        let now = Instant::now();
+        if next_tick < now {
             warn!(
                 "One iteration of a force feedback loop took more than {}ms!",
                 TICK_DURATION
             );
-        } else {
-            thread::sleep(sleep_dur - dur);
+            next_tick = now;
         }
-        tick.inc();
     }
 }
 
+fn handle_message(
+    ev: Message,
+    effects: &mut VecMap<Effect>,
+    devices: &mut VecMap<Device>,
+    tick: Ticks,
+    completion_events: &mut Vec<Event>,
+    tx: &Sender<FfMessage>,
+) {
+    let immediate = ev.is_immediate();
+    if ev.use_trace_level() {
+        trace!("New ff event: {:?}", ev);
+    } else {
+        debug!("New ff event: {:?}", ev);
+    }
+
+    match ev {
+        Message::Create { id, effect } => {
+            effects.insert(id, (*effect).into());
+        }
+        Message::Play { id } => {
+            if let Some(effect) = effects.get_mut(id) {
+                effect.source.state = EffectState::Playing { since: tick }
+            } else {
+                error!("{:?} with wrong ID", ev);
+            }
+        }
+        Message::Stop { id } => {
+            if let Some(effect) = effects.get_mut(id) {
+                effect.source.state = EffectState::Stopped
+            } else {
+                error!("{:?} with wrong ID", ev);
+            }
+        }
+        Message::Open { id, device } => {
+            devices.insert(id, device.into());
+        }
+        Message::Close { id } => {
+            devices.remove(id);
+        }
+        Message::SetListenerPosition { id, position } => {
+            if let Some(device) = devices.get_mut(id) {
+                device.position = position;
+            } else {
+                error!("{:?} with wrong ID", ev);
+            }
+        }
+        Message::HandleCloned { id } => {
+            if let Some(effect) = effects.get_mut(id) {
+                effect.inc();
+            } else {
+                error!("{:?} with wrong ID", ev);
+            }
+        }
+        Message::HandleDropped { id } => {
+            let mut drop = false;
+            if let Some(effect) = effects.get_mut(id) {
+                if effect.dec() == 0 {
+                    drop = true;
+                }
+            } else {
+                error!("{:?} with wrong ID", ev);
+            }
+
+            if drop {
+                effects.remove(id);
+            }
+        }
+        Message::SetGamepads { id, gamepads } => {
+            if let Some(eff) = effects.get_mut(id) {
+                eff.source.devices = gamepads;
+            } else {
+                error!("Invalid effect id {} when changing gamepads.", id);
+            }
+        }
+        Message::AddGamepad { id, gamepad_id } => {
+            if let Some(eff) = effects.get_mut(id) {
+                eff.source.devices.insert(gamepad_id.0, ());
+            } else {
+                error!("Invalid effect id {} when changing gamepads.", id);
+            }
+        }
+        Message::SetRepeat { id, repeat } => {
+            if let Some(eff) = effects.get_mut(id) {
+                eff.source.repeat = repeat;
+            } else {
+                error!("Invalid effect id {} when changing repeat mode.", id);
+            }
+        }
+        Message::SetDistanceModel { id, model } => {
+            if let Some(eff) = effects.get_mut(id) {
+                eff.source.distance_model = model;
+            } else {
+                error!("Invalid effect id {} when changing distance model.", id);
+            }
+        }
+        Message::SetPosition { id, position } => {
+            if let Some(eff) = effects.get_mut(id) {
+                eff.source.position = position;
+            } else {
+                error!("Invalid effect id {}.", id);
+            }
+        }
+        Message::SetGain { id, gain } => {
+            if let Some(eff) = effects.get_mut(id) {
+                eff.source.set_gain(gain);
+            } else {
+                error!("Invalid effect id {} when changing effect gain.", id);
+            }
+        }
+        Message::SetGainRamp { id, ramp } => {
+            if let Some(eff) = effects.get_mut(id) {
+                eff.source.set_gain_ramp(ramp);
+            } else {
+                error!("Invalid effect id {} when changing effect gain ramp.", id);
+            }
+        }
+        Message::SetPriority { id, priority } => {
+            if let Some(eff) = effects.get_mut(id) {
+                eff.source.priority = priority;
+            } else {
+                error!("Invalid effect id {} when changing effect priority.", id);
+            }
+        }
+        Message::SetMixingPolicy { id, policy } => {
+            if let Some(device) = devices.get_mut(id) {
+                device.mixing = policy;
+            } else {
+                error!("{:?} with wrong ID", ev);
+            }
+        }
+        Message::SetMotors { id, strong, weak } => {
+            if let Some(device) = devices.get_mut(id) {
+                device.motors = if strong == 0 && weak == 0 {
+                    None
+                } else {
+                    Some(Magnitude { strong, weak })
+                };
+            } else {
+                error!("{:?} with wrong ID", ev);
+            }
+        }
+        Message::SetEnabled { id, enabled } => {
+            if let Some(device) = devices.get_mut(id) {
+                device.enabled = enabled;
+            } else {
+                error!("{:?} with wrong ID", ev);
+            }
+        }
+        Message::Rumble {
+            id,
+            strong,
+            weak,
+            duration,
+        } => {
+            if let Some(device) = devices.get_mut(id) {
+                device.rumble = Some((Magnitude { strong, weak }, tick + duration));
+            } else {
+                error!("{:?} with wrong ID", ev);
+            }
+        }
+    }
+
+    if immediate {
+        combine_and_play(effects, devices, tick, completion_events);
+        send_completion_events(tx, completion_events);
+    }
+}
+
+fn send_completion_events(tx: &Sender<FfMessage>, completion_events: &mut Vec<Event>) {
+    completion_events.iter().for_each(|ev| {
+        let _ = tx.send(FfMessage::EffectCompleted { event: *ev });
+    });
+    completion_events.clear();
+}
+
 pub(crate) fn init() -> (Sender<Message>, Receiver<FfMessage>) {
     let (tx, _rx) = mpsc::channel();
     let (_tx2, rx2) = mpsc::channel();
@@ -298,12 +448,46 @@ fn combine_and_play(
 ) {
     for (dev_id, dev) in devices {
         let mut magnitude = Magnitude::zero();
+        // Priority of effects in `magnitude`, used by `MixingPolicy::PriorityOverride`.
+        let mut priority = None;
         for (_, ref mut effect) in effects.iter_mut() {
             if effect.devices.contains_key(dev_id) {
-                magnitude += effect.combine_base_effects(tick, dev.position);
+                let effect_magnitude = effect.combine_base_effects(tick, dev.position);
                 completion_events.extend(effect.flush_completion_events());
+                if effect_magnitude.is_zero() {
+                    continue;
+                }
+
+                match dev.mixing {
+                    MixingPolicy::Sum => magnitude += effect_magnitude,
+                    MixingPolicy::Max => magnitude = magnitude.max(effect_magnitude),
+                    MixingPolicy::PriorityOverride => match priority {
+                        Some(p) if p > effect.priority => (),
+                        Some(p) if p == effect.priority => {
+                            magnitude = magnitude.max(effect_magnitude)
+                        }
+                        _ => {
+                            priority = Some(effect.priority);
+                            magnitude = effect_magnitude;
+                        }
+                    },
+                }
             }
         }
+        match dev.rumble {
+            Some((rumble, until)) if tick < until => match dev.mixing {
+                MixingPolicy::Sum => magnitude += rumble,
+                _ => magnitude = magnitude.max(rumble),
+            },
+            Some(_) => dev.rumble = None,
+            None => (),
+        }
+        if let Some(motors) = dev.motors {
+            magnitude = motors;
+        }
+        if !dev.enabled {
+            magnitude = Magnitude::zero();
+        }
         trace!(
             "({:?}) Setting ff state of {:?} to {:?}",
             tick,
@@ -317,3 +501,196 @@ fn combine_and_play(
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ff::{BaseEffect, BaseEffectType, Replay};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, Clone, Default)]
+    struct MockDevice(Arc<Mutex<(u16, u16)>>);
+
+    impl BackendFfDevice for MockDevice {
+        fn set_ff_state(&mut self, strong: u16, weak: u16, _min_duration: Duration) {
+            *self.0.lock().unwrap() = (strong, weak);
+        }
+    }
+
+    /// Records every call to `set_ff_state()`.
+    #[derive(Debug, Clone, Default)]
+    struct RecordingDevice(Arc<Mutex<Vec<(u16, u16)>>>);
+
+    impl BackendFfDevice for RecordingDevice {
+        fn set_ff_state(&mut self, strong: u16, weak: u16, _min_duration: Duration) {
+            self.0.lock().unwrap().push((strong, weak));
+        }
+    }
+
+    #[test]
+    fn raw_motors_override_effects() {
+        let state = MockDevice::default();
+        let mut devices = VecMap::<Device>::new();
+        devices.insert(
+            0,
+            (Box::new(state.clone()) as Box<dyn BackendFfDevice>).into(),
+        );
+        let mut effects = VecMap::<Effect>::new();
+        let mut events = Vec::new();
+
+        combine_and_play(&mut effects, &mut devices, Ticks(0), &mut events);
+        assert_eq!(*state.0.lock().unwrap(), (0, 0));
+
+        devices[0].motors = Some(Magnitude {
+            strong: 1000,
+            weak: 20,
+        });
+        combine_and_play(&mut effects, &mut devices, Ticks(1), &mut events);
+        assert_eq!(*state.0.lock().unwrap(), (1000, 20));
+
+        devices[0].enabled = false;
+        combine_and_play(&mut effects, &mut devices, Ticks(2), &mut events);
+        assert_eq!(*state.0.lock().unwrap(), (0, 0));
+
+        devices[0].enabled = true;
+        devices[0].motors = None;
+        combine_and_play(&mut effects, &mut devices, Ticks(3), &mut events);
+        assert_eq!(*state.0.lock().unwrap(), (0, 0));
+    }
+
+    fn playing_effect(kinds: &[BaseEffectType], priority: u8) -> Effect {
+        let base_effects = kinds
+            .iter()
+            .map(|&kind| BaseEffect {
+                kind,
+                scheduling: Replay {
+                    play_for: Ticks(100),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .collect();
+        let mut devices = VecMap::new();
+        devices.insert(0, ());
+        let mut effect = EffectSource::new(
+            base_effects,
+            devices,
+            Repeat::Infinitely,
+            DistanceModel::None,
+            [0.0, 0.0, 0.0],
+            1.0,
+            Ticks(0),
+        );
+        effect.state = EffectState::Playing { since: Ticks(0) };
+        effect.priority = priority;
+        effect.into()
+    }
+
+    #[test]
+    fn mixing_policies() {
+        let state = MockDevice::default();
+        let mut devices = VecMap::<Device>::new();
+        devices.insert(
+            0,
+            (Box::new(state.clone()) as Box<dyn BackendFfDevice>).into(),
+        );
+        let mut effects = VecMap::<Effect>::new();
+        let mut events = Vec::new();
+        // Ambient rumble and higher priority impact.
+        effects.insert(
+            0,
+            playing_effect(
+                &[
+                    BaseEffectType::Strong { magnitude: 30_000 },
+                    BaseEffectType::Weak { magnitude: 20_000 },
+                ],
+                0,
+            ),
+        );
+        effects.insert(
+            1,
+            playing_effect(&[BaseEffectType::Strong { magnitude: 50_000 }], 1),
+        );
+
+        // Default is `Max`.
+        combine_and_play(&mut effects, &mut devices, Ticks(0), &mut events);
+        assert_eq!(*state.0.lock().unwrap(), (50_000, 20_000));
+
+        devices[0].mixing = MixingPolicy::Sum;
+        combine_and_play(&mut effects, &mut devices, Ticks(1), &mut events);
+        assert_eq!(*state.0.lock().unwrap(), (u16::MAX, 20_000));
+
+        devices[0].mixing = MixingPolicy::PriorityOverride;
+        combine_and_play(&mut effects, &mut devices, Ticks(2), &mut events);
+        assert_eq!(*state.0.lock().unwrap(), (50_000, 0));
+
+        // Silent effect doesn't override anything.
+        effects[1].state = EffectState::Stopped;
+        combine_and_play(&mut effects, &mut devices, Ticks(3), &mut events);
+        assert_eq!(*state.0.lock().unwrap(), (30_000, 20_000));
+    }
+
+    #[test]
+    fn rumble_is_played_immediately() {
+        let state = RecordingDevice::default();
+        let mut devices = VecMap::<Device>::new();
+        let mut effects = VecMap::<Effect>::new();
+        let mut events = Vec::new();
+        let (tx, _rx) = mpsc::channel();
+
+        handle_message(
+            Message::Open {
+                id: 0,
+                device: Box::new(state.clone()),
+            },
+            &mut effects,
+            &mut devices,
+            Ticks(0),
+            &mut events,
+            &tx,
+        );
+        assert!(state.0.lock().unwrap().is_empty());
+
+        handle_message(
+            Message::Rumble {
+                id: 0,
+                strong: 1000,
+                weak: 0,
+                duration: Ticks(10),
+            },
+            &mut effects,
+            &mut devices,
+            Ticks(0),
+            &mut events,
+            &tx,
+        );
+        assert_eq!(*state.0.lock().unwrap(), [(1000, 0)]);
+    }
+
+    #[test]
+    fn one_shot_rumble_expires() {
+        let state = MockDevice::default();
+        let mut devices = VecMap::<Device>::new();
+        devices.insert(
+            0,
+            (Box::new(state.clone()) as Box<dyn BackendFfDevice>).into(),
+        );
+        let mut effects = VecMap::<Effect>::new();
+        let mut events = Vec::new();
+
+        devices[0].rumble = Some((
+            Magnitude {
+                strong: 500,
+                weak: 600,
+            },
+            Ticks(2),
+        ));
+        combine_and_play(&mut effects, &mut devices, Ticks(0), &mut events);
+        assert_eq!(*state.0.lock().unwrap(), (500, 600));
+        combine_and_play(&mut effects, &mut devices, Ticks(1), &mut events);
+        assert_eq!(*state.0.lock().unwrap(), (500, 600));
+        combine_and_play(&mut effects, &mut devices, Ticks(2), &mut events);
+        assert_eq!(*state.0.lock().unwrap(), (0, 0));
+        assert!(devices[0].rumble.is_none());
+    }
+}
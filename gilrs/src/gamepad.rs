@@ -6,20 +6,24 @@
 // copied, modified, or distributed except according to those terms.
 
 use crate::{
+    calibration::{CalibrationProfile, ObservedRange, TriggerCalibration},
     ev::{
+        filter::{DebounceState, DpadDebounce, SocdState},
         state::{AxisData, ButtonData, GamepadState},
-        Axis, AxisOrBtn, Button, Code, Event, EventType,
+        Axis, AxisOrBtn, Button, Code, Event, EventType, Side,
     },
     ff::{
         server::{self, FfMessage, Message},
-        Error as FfError,
+        BaseEffectType, Error as FfError, MixingPolicy,
     },
-    mapping::{Mapping, MappingData, MappingDb},
+    mapping::{CombinedTriggers, Mapping, MappingData, MappingDb},
     utils, MappingError,
 };
 
 use gilrs_core::{
-    self, AxisInfo, Error as PlatformError, Event as RawEvent, EventType as RawEventType,
+    self,
+    backend::{BackendGamepad, GamepadBackend},
+    AxisInfo, Error as PlatformError, EvCode, Event as RawEvent, EventType as RawEventType,
 };
 
 use uuid::Uuid;
@@ -29,11 +33,12 @@ use std::{
     collections::VecDeque,
     error,
     fmt::{self, Display},
+    io,
     sync::mpsc::{Receiver, Sender},
-    time::Duration,
+    time::{Duration, SystemTime},
 };
 
-pub use gilrs_core::PowerInfo;
+pub use gilrs_core::{Capabilities, DeviceSubtype, DisconnectReason, PowerInfo};
 
 #[cfg(feature = "serde-serialize")]
 use serde::{Deserialize, Serialize};
@@ -131,16 +136,19 @@ const DEFAULT_DEADZONE: f32 = 0.1;
 ///
 #[derive(Debug)]
 pub struct Gilrs {
-    inner: gilrs_core::Gilrs,
+    inner: Box<dyn GamepadBackend>,
     next_id: usize,
     tx: Sender<Message>,
     rx: Receiver<FfMessage>,
     counter: u64,
     mappings: MappingDb,
     default_filters: bool,
+    dpad_debounce: DpadDebounce,
     events: VecDeque<Event>,
     axis_to_btn_pressed: f32,
     axis_to_btn_released: f32,
+    split_triggers: bool,
+    event_logger: Option<EventLogger>,
     pub(crate) update_state: bool,
     pub(crate) gamepads_data: Vec<GamepadData>,
 }
@@ -155,13 +163,20 @@ impl Gilrs {
     /// Returns next pending event. If there is no pending event, `None` is
     /// returned. This function will not block current thread and should be safe
     /// to call in async context. Doesn't block the thread it is run in
+    ///
+    /// If automatic state updates are enabled (the default), cached state is updated with returned
+    /// event before this function returns, so `gilrs.gamepad(ev.id).is_pressed(btn)` already
+    /// reflects `ButtonPressed(btn, _)` that was just returned. This holds for events from backend
+    /// and from [`insert_event()`](Self::insert_event). Button's value is updated by
+    /// `ButtonChanged` event that follows `ButtonPressed` and `ButtonReleased`.
     pub fn next_event(&mut self) -> Option<Event> {
         self.next_event_inner(false, None)
     }
 
     /// Same as [Gilrs::next_event], but blocks the thread it is run in. Useful
     /// for apps that aren't run inside a loop and just react to the user's input,
-    /// like GUI apps.
+    /// like GUI apps. Cached state is updated before event is returned, same as in
+    /// `next_event()`.
     ///
     /// ## Platform support
     ///
@@ -170,6 +185,35 @@ impl Gilrs {
         self.next_event_inner(true, timeout)
     }
 
+    /// Appends all pending events to `buf` and returns number of appended events.
+    ///
+    /// This behaves exactly like calling [`next_event()`](Gilrs::next_event) until it returns
+    /// `None`, but lets you reuse one buffer between frames instead of handling events one by one.
+    ///
+    /// ```
+    /// # let mut gilrs = gilrs::Gilrs::new().unwrap();
+    /// let mut events = Vec::new();
+    ///
+    /// loop {
+    ///     events.clear();
+    ///     gilrs.poll_events(&mut events);
+    ///
+    ///     for event in &events {
+    ///         println!("{:?}", event);
+    ///     }
+    ///     # break;
+    /// }
+    /// ```
+    pub fn poll_events(&mut self, buf: &mut Vec<Event>) -> usize {
+        let len = buf.len();
+
+        while let Some(ev) = self.next_event() {
+            buf.push(ev);
+        }
+
+        buf.len() - len
+    }
+
     fn next_event_inner(
         &mut self,
         is_blocking: bool,
@@ -179,9 +223,22 @@ impl Gilrs {
 
         let ev = if self.default_filters {
             let jitter_filter = Jitter::new();
+            let dpad_debounce = self.dpad_debounce;
             loop {
+                // Don't block for longer than debounce window if it's holding an event.
+                let pending = is_blocking && self.is_dpad_debounce_pending();
+                let timeout = if pending {
+                    Some(
+                        blocking_timeout
+                            .map_or(dpad_debounce.window, |t| t.min(dpad_debounce.window)),
+                    )
+                } else {
+                    blocking_timeout
+                };
+
                 let ev = self
-                    .next_event_priv(is_blocking, blocking_timeout)
+                    .next_event_logged(is_blocking, timeout)
+                    .filter_ev(&dpad_debounce, self)
                     .filter_ev(&axis_dpad_to_button, self)
                     .filter_ev(&jitter_filter, self)
                     .filter_ev(&deadzone, self);
@@ -189,11 +246,12 @@ impl Gilrs {
                 // Skip all dropped events, there is no reason to return them
                 match ev {
                     Some(ev) if ev.is_dropped() => (),
+                    None if pending && blocking_timeout.is_none() => (),
                     _ => break ev,
                 }
             }
         } else {
-            self.next_event_priv(is_blocking, blocking_timeout)
+            self.next_event_logged(is_blocking, blocking_timeout)
         };
 
         if self.update_state {
@@ -205,6 +263,27 @@ impl Gilrs {
         ev
     }
 
+    fn is_dpad_debounce_pending(&self) -> bool {
+        self.gamepads_data
+            .iter()
+            .any(|data| data.dpad_debounce.iter().any(|s| s.is_pending()))
+    }
+
+    /// Returns next pending event and passes it to event logger.
+    fn next_event_logged(
+        &mut self,
+        is_blocking: bool,
+        blocking_timeout: Option<Duration>,
+    ) -> Option<Event> {
+        let ev = self.next_event_priv(is_blocking, blocking_timeout);
+
+        if let (Some(logger), Some(ev)) = (self.event_logger.as_mut(), ev.as_ref()) {
+            (logger.0)(ev.id.0, ev);
+        }
+
+        ev
+    }
+
     /// Returns next pending event.
     fn next_event_priv(
         &mut self,
@@ -217,152 +296,275 @@ impl Gilrs {
             }
         }
         if let Some(ev) = self.events.pop_front() {
-            Some(ev)
-        } else {
+            return Some(ev);
+        }
+
+        // Raw events that don't produce any event are skipped.
+        loop {
             let event = if is_blocking {
                 self.inner.next_event_blocking(blocking_timeout)
             } else {
                 self.inner.next_event()
             };
 
-            match event {
-                Some(RawEvent { id, event, time }) => {
-                    trace!("Original event: {:?}", RawEvent { id, event, time });
-                    let id = GamepadId(id);
-
-                    let event = match event {
-                        RawEventType::ButtonPressed(nec) => {
-                            let nec = Code(nec);
-                            match self.gamepad(id).axis_or_btn_name(nec) {
-                                Some(AxisOrBtn::Btn(b)) => {
-                                    self.events.push_back(Event {
-                                        id,
-                                        time,
-                                        event: EventType::ButtonChanged(b, 1.0, nec),
-                                    });
+            let RawEvent { id, event, time } = event?;
+            trace!("Original event: {:?}", RawEvent { id, event, time });
+            let id = GamepadId(id);
+
+            // Backend may come from outside of gilrs, so don't trust it to only report events of
+            // connected gamepads.
+            if event != RawEventType::Connected {
+                let enabled = match self.gamepads_data.get(id.0) {
+                    Some(data) => data.enabled,
+                    None => {
+                        error!(
+                            "Backend error: got {:?} for gamepad {} that was never connected",
+                            event, id.0
+                        );
+                        continue;
+                    }
+                };
 
-                                    EventType::ButtonPressed(b, nec)
-                                }
-                                Some(AxisOrBtn::Axis(a)) => EventType::AxisChanged(a, 1.0, nec),
-                                None => {
-                                    self.events.push_back(Event {
-                                        id,
-                                        time,
-                                        event: EventType::ButtonChanged(Button::Unknown, 1.0, nec),
-                                    });
+                if !enabled && !matches!(event, RawEventType::Disconnected(_)) {
+                    continue;
+                }
+            }
+
+            let event = match event {
+                RawEventType::ButtonPressed(nec) => {
+                    let nec = Code(nec);
+                    match self.gamepad(id).axis_or_btn_name(nec) {
+                        Some(AxisOrBtn::Btn(b)) => {
+                            self.events.push_back(Event {
+                                id,
+                                time,
+                                event: EventType::ButtonChanged(b, 1.0, nec),
+                            });
+
+                            EventType::ButtonPressed(b, nec)
+                        }
+                        Some(AxisOrBtn::Axis(a)) => EventType::AxisChanged(a, 1.0, nec),
+                        None => {
+                            self.events.push_back(Event {
+                                id,
+                                time,
+                                event: EventType::ButtonChanged(Button::Unknown, 1.0, nec),
+                            });
+
+                            EventType::ButtonPressed(Button::Unknown, nec)
+                        }
+                    }
+                }
+                RawEventType::ButtonReleased(nec) => {
+                    let nec = Code(nec);
+                    match self.gamepad(id).axis_or_btn_name(nec) {
+                        Some(AxisOrBtn::Btn(b)) => {
+                            self.events.push_back(Event {
+                                id,
+                                time,
+                                event: EventType::ButtonChanged(b, 0.0, nec),
+                            });
+
+                            EventType::ButtonReleased(b, nec)
+                        }
+                        Some(AxisOrBtn::Axis(a)) => EventType::AxisChanged(a, 0.0, nec),
+                        None => {
+                            self.events.push_back(Event {
+                                id,
+                                time,
+                                event: EventType::ButtonChanged(Button::Unknown, 0.0, nec),
+                            });
+
+                            EventType::ButtonReleased(Button::Unknown, nec)
+                        }
+                    }
+                }
+                RawEventType::AxisValueChanged(val, nec) => {
+                    let axis_info = match self.gamepad(id).inner.axis_info(nec) {
+                        Some(info) => *info,
+                        None => {
+                            error!(
+                                "Backend error: axis {:?} of gamepad {} has no AxisInfo",
+                                nec, id.0
+                            );
+                            continue;
+                        }
+                    };
+                    let nec = Code(nec);
+
+                    if let Some(triggers) = self.combined_triggers(id, nec.0) {
+                        let raw_val = val;
+                        let val = axis_value(&axis_info, val, Axis::Unknown);
+                        let (left, right) = triggers.split(val);
+                        let halves = [
+                            (Button::LeftTrigger2, Code(triggers.left), left),
+                            (Button::RightTrigger2, Code(triggers.right), right),
+                        ];
+
+                        // Only report triggers which value changed.
+                        for (i, (b, nec, val)) in halves.into_iter().enumerate() {
+                            if self.update_state {
+                                self.gamepads_data[id.0].state.set_btn_raw(nec, raw_val);
+                            }
 
-                                    EventType::ButtonPressed(Button::Unknown, nec)
+                            let last = &mut self.gamepads_data[id.0].split_trigger_values[i];
+                            if *last != val {
+                                *last = val;
+                                let (event, next) = self.axis_to_btn(id, b, val, nec);
+                                self.events.push_back(Event { id, time, event });
+                                if let Some(event) = next {
+                                    self.events.push_back(Event { id, time, event });
                                 }
                             }
                         }
-                        RawEventType::ButtonReleased(nec) => {
-                            let nec = Code(nec);
-                            match self.gamepad(id).axis_or_btn_name(nec) {
-                                Some(AxisOrBtn::Btn(b)) => {
-                                    self.events.push_back(Event {
-                                        id,
-                                        time,
-                                        event: EventType::ButtonChanged(b, 0.0, nec),
-                                    });
 
-                                    EventType::ButtonReleased(b, nec)
-                                }
-                                Some(AxisOrBtn::Axis(a)) => EventType::AxisChanged(a, 0.0, nec),
-                                None => {
-                                    self.events.push_back(Event {
-                                        id,
-                                        time,
-                                        event: EventType::ButtonChanged(Button::Unknown, 0.0, nec),
-                                    });
+                        match self.events.pop_front() {
+                            Some(ev) => return Some(ev),
+                            None => continue,
+                        }
+                    }
 
-                                    EventType::ButtonReleased(Button::Unknown, nec)
-                                }
-                            }
+                    let name = self.gamepad(id).axis_or_btn_name(nec);
+                    if self.update_state {
+                        let state = &mut self.gamepads_data[id.0].state;
+                        match name {
+                            Some(AxisOrBtn::Btn(_)) => state.set_btn_raw(nec, val),
+                            _ => state.set_axis_raw(nec, val),
                         }
-                        RawEventType::AxisValueChanged(val, nec) => {
-                            // Let's trust at least our backend code
-                            let axis_info = *self.gamepad(id).inner.axis_info(nec).unwrap();
-                            let nec = Code(nec);
-
-                            match self.gamepad(id).axis_or_btn_name(nec) {
-                                Some(AxisOrBtn::Btn(b)) => {
-                                    let val = btn_value(&axis_info, val);
-
-                                    if val >= self.axis_to_btn_pressed
-                                        && !self.gamepad(id).state().is_pressed(nec)
-                                    {
-                                        self.events.push_back(Event {
-                                            id,
-                                            time,
-                                            event: EventType::ButtonChanged(b, val, nec),
-                                        });
-
-                                        EventType::ButtonPressed(b, nec)
-                                    } else if val <= self.axis_to_btn_released
-                                        && self.gamepad(id).state().is_pressed(nec)
-                                    {
-                                        self.events.push_back(Event {
-                                            id,
-                                            time,
-                                            event: EventType::ButtonChanged(b, val, nec),
-                                        });
-
-                                        EventType::ButtonReleased(b, nec)
-                                    } else {
-                                        EventType::ButtonChanged(b, val, nec)
-                                    }
-                                }
-                                Some(AxisOrBtn::Axis(a)) => {
-                                    EventType::AxisChanged(a, axis_value(&axis_info, val, a), nec)
-                                }
-                                None => EventType::AxisChanged(
-                                    Axis::Unknown,
-                                    axis_value(&axis_info, val, Axis::Unknown),
-                                    nec,
-                                ),
+                    }
+
+                    match name {
+                        Some(AxisOrBtn::Btn(b)) => {
+                            let (event, next) =
+                                self.axis_to_btn(id, b, btn_value(&axis_info, val), nec);
+                            if let Some(event) = next {
+                                self.events.push_back(Event { id, time, event });
                             }
+
+                            event
                         }
-                        RawEventType::Connected => {
-                            match id.0.cmp(&self.gamepads_data.len()) {
-                                Ordering::Equal => {
-                                    self.gamepads_data.push(GamepadData::new(
-                                        id,
-                                        self.tx.clone(),
-                                        self.inner.gamepad(id.0).unwrap(),
-                                        &self.mappings,
-                                    ));
-                                }
-                                Ordering::Less => {
-                                    self.gamepads_data[id.0] = GamepadData::new(
-                                        id,
-                                        self.tx.clone(),
-                                        self.inner.gamepad(id.0).unwrap(),
-                                        &self.mappings,
-                                    );
+                        Some(AxisOrBtn::Axis(a)) => {
+                            let val = axis_value(&axis_info, val, a);
+                            let val = self.gamepads_data[id.0].profile.apply(a, val);
+                            EventType::AxisChanged(a, val, nec)
+                        }
+                        None => EventType::AxisChanged(
+                            Axis::Unknown,
+                            axis_value(&axis_info, val, Axis::Unknown),
+                            nec,
+                        ),
+                    }
+                }
+                RawEventType::Connected => {
+                    let gamepad = match self.inner.gamepad(id.0) {
+                        Some(gamepad) => gamepad,
+                        None => {
+                            error!("Backend error: got Connected event for invalid id {}", id.0);
+                            continue;
+                        }
+                    };
+
+                    match id.0.cmp(&self.gamepads_data.len()) {
+                        Ordering::Equal => {
+                            self.gamepads_data.push(GamepadData::new(
+                                id,
+                                self.tx.clone(),
+                                gamepad,
+                                &self.mappings,
+                            ));
+                        }
+                        Ordering::Less => {
+                            let mut data =
+                                GamepadData::new(id, self.tx.clone(), gamepad, &self.mappings);
+                            let old = &self.gamepads_data[id.0];
+                            // Backends reuse ID when device is reconnected, but other device can
+                            // also take its place. Only keep user's settings for the same device.
+                            let same_device = old.uuid == data.uuid
+                                && old.disconnect_reason != Some(DisconnectReason::Replaced);
+                            if same_device {
+                                data.profile = old.profile.clone();
+                                if !old.enabled {
+                                    data.enabled = false;
+                                    let _ = self.tx.send(Message::SetEnabled {
+                                        id: id.0,
+                                        enabled: false,
+                                    });
                                 }
-                                Ordering::Greater => {
-                                    error!(
-                                        "Platform implementation error: got Connected event with \
-                                         id {}, when expected id {}",
-                                        id.0,
-                                        self.gamepads_data.len()
-                                    );
+                                let policy = old.ff_mixing;
+                                if policy != MixingPolicy::default() {
+                                    data.ff_mixing = policy;
+                                    let _ =
+                                        self.tx.send(Message::SetMixingPolicy { id: id.0, policy });
                                 }
                             }
-
-                            EventType::Connected
+                            self.gamepads_data[id.0] = data;
                         }
-                        RawEventType::Disconnected => {
-                            let _ = self.tx.send(Message::Close { id: id.0 });
-
-                            EventType::Disconnected
+                        Ordering::Greater => {
+                            error!(
+                                "Backend error: got Connected event with id {}, when expected \
+                                 id {}",
+                                id.0,
+                                self.gamepads_data.len()
+                            );
+                            continue;
                         }
-                    };
+                    }
 
-                    Some(Event { id, event, time })
+                    EventType::Connected
                 }
-                None => None,
-            }
+                RawEventType::Disconnected(reason) => {
+                    let _ = self.tx.send(Message::Close { id: id.0 });
+                    self.gamepads_data[id.0].disconnect_reason = Some(reason);
+
+                    EventType::Disconnected
+                }
+                RawEventType::PowerChanged(info) => EventType::PowerChanged(info),
+            };
+
+            return Some(Event { id, event, time });
+        }
+    }
+
+    /// Returns axis that should be split into two triggers if `nec` is such axis.
+    fn combined_triggers(&self, id: GamepadId, nec: EvCode) -> Option<CombinedTriggers> {
+        if !self.split_triggers {
+            return None;
+        }
+
+        self.gamepads_data[id.0]
+            .mapping
+            .combined_triggers()
+            .filter(|triggers| triggers.code == nec)
+    }
+
+    /// Converts value of axis mapped to button to event. Second event, if any, should be emitted
+    /// right after first one.
+    fn axis_to_btn(
+        &mut self,
+        id: GamepadId,
+        btn: Button,
+        val: f32,
+        nec: Code,
+    ) -> (EventType, Option<EventType>) {
+        let data = &mut self.gamepads_data[id.0];
+        let val = data.calibrate_trigger(btn, val);
+        let (pressed, released) = data
+            .profile
+            .press_threshold(btn, (self.axis_to_btn_pressed, self.axis_to_btn_released));
+        let is_pressed = self.gamepad(id).state().is_pressed(nec);
+
+        if val >= pressed && !is_pressed {
+            (
+                EventType::ButtonPressed(btn, nec),
+                Some(EventType::ButtonChanged(btn, val, nec)),
+            )
+        } else if val <= released && is_pressed {
+            (
+                EventType::ButtonReleased(btn, nec),
+                Some(EventType::ButtonChanged(btn, val, nec)),
+            )
+        } else {
+            (EventType::ButtonChanged(btn, val, nec), None)
         }
     }
 
@@ -370,6 +572,10 @@ impl Gilrs {
     ///
     /// Please note, that it's not necessary to call this function unless you modify events by using
     /// additional filters and disabled automatic updates when creating `Gilrs`.
+    ///
+    /// This is the same function `next_event()` uses when automatic updates are enabled, so
+    /// events from every source (backend, [`insert_event()`](Self::insert_event) or your own
+    /// filters) change cached state in the same way. Events with ID of unknown gamepad are ignored.
     pub fn update(&mut self, event: &Event) {
         use crate::EventType::*;
 
@@ -398,6 +604,7 @@ impl Gilrs {
                     .update_axis(nec, AxisData::new(value, counter, event.time));
             }
             Disconnected | Connected | Dropped | ForceFeedbackEffectCompleted => (),
+            PowerChanged(_) => (),
         }
     }
 
@@ -405,6 +612,10 @@ impl Gilrs {
     /// determine when last event happened. You probably want to use this function in your update
     /// loop after processing events.
     pub fn inc(&mut self) {
+        for data in &mut self.gamepads_data {
+            data.state.next_frame();
+        }
+
         // Counter is 62bit. See `ButtonData`.
         if self.counter == 0x3FFF_FFFF_FFFF_FFFF {
             self.counter = 0;
@@ -424,6 +635,21 @@ impl Gilrs {
         self.counter = 0;
     }
 
+    /// Returns false if gamepad events can't be received anymore, for example because platform's
+    /// event thread panicked. In that case `next_event()` will only return events that were
+    /// already queued and gamepads keep their last known state, so application may want to tell
+    /// the user or create new `Gilrs`.
+    pub fn is_alive(&self) -> bool {
+        self.inner.is_alive()
+    }
+
+    /// Returns how often power supply state of gamepads is refreshed in the background, see
+    /// [`GilrsBuilder::set_power_info_interval()`]. Returns `None` if backend doesn't refresh it,
+    /// in which case `EventType::PowerChanged` is never emitted.
+    pub fn power_info_interval(&self) -> Option<Duration> {
+        self.inner.power_info_interval()
+    }
+
     fn finish_gamepads_creation(&mut self) {
         let tx = self.tx.clone();
         for id in 0..self.inner.last_gamepad_hint() {
@@ -440,6 +666,15 @@ impl Gilrs {
     /// Returns handle to gamepad with given ID. Unlike `connected_gamepad()`, this function will
     /// also return handle to gamepad that is currently disconnected.
     ///
+    /// # Panics
+    ///
+    /// Panics if `id` doesn't belong to this `Gilrs` instance. IDs from events and
+    /// [`gamepads()`](Gilrs::gamepads) are always valid. Use [`get_gamepad()`](Gilrs::get_gamepad)
+    /// or `connected_gamepad()` if `id` may come from somewhere else.
+    ///
+    /// Methods of `Gilrs` that take `GamepadId` and change gamepad's settings do nothing (or
+    /// return error) for such IDs.
+    ///
     /// ```
     /// # let mut gilrs = gilrs::Gilrs::new().unwrap();
     /// use gilrs::{Button, EventType};
@@ -466,6 +701,19 @@ impl Gilrs {
         }
     }
 
+    /// Returns handle to gamepad with given ID, connected or not, or `None` if `id` doesn't belong
+    /// to this `Gilrs` instance.
+    ///
+    /// This is fallible version of [`gamepad()`](Gilrs::gamepad). Use it when `id` doesn't come
+    /// from event or [`gamepads()`](Gilrs::gamepads), for example when it was read from config
+    /// file.
+    pub fn get_gamepad(&self, id: GamepadId) -> Option<Gamepad<'_>> {
+        let data = self.gamepads_data.get(id.0)?;
+        let inner = self.inner.gamepad(id.0)?;
+
+        Some(Gamepad { inner, data })
+    }
+
     /// Returns a reference to connected gamepad or `None`.
     pub fn connected_gamepad(&self, id: GamepadId) -> Option<Gamepad<'_>> {
         // Make sure that it will not panic even with invalid GamepadId, so ConnectedGamepadIterator
@@ -497,11 +745,99 @@ impl Gilrs {
         ConnectedGamepadsIterator(self, 0)
     }
 
+    /// Returns number of connected gamepads, same as number of items returned by
+    /// [`gamepads()`](Gilrs::gamepads).
+    pub fn len(&self) -> usize {
+        self.gamepads().count()
+    }
+
+    /// Returns true if no gamepad is connected.
+    pub fn is_empty(&self) -> bool {
+        self.gamepads().next().is_none()
+    }
+
+    /// Rumbles all connected gamepads once with given motor speeds for `duration`. Gamepads
+    /// without force feedback support are skipped.
+    ///
+    /// See [`Gamepad::rumble()`].
+    pub fn rumble_all(&self, strong: u16, weak: u16, duration: Duration) {
+        for (_, gamepad) in self.gamepads() {
+            if gamepad.is_ff_supported() {
+                let _ = gamepad.rumble(strong, weak, duration);
+            }
+        }
+    }
+
     /// Adds `ev` at the end of internal event queue. It can later be retrieved with `next_event()`.
+    ///
+    /// Inserted event is processed like events from backend: it goes through default filters (if
+    /// enabled) and updates cached state (if automatic updates are enabled).
     pub fn insert_event(&mut self, ev: Event) {
         self.events.push_back(ev);
     }
 
+    /// Discards all pending events and resets state of all gamepads to neutral.
+    ///
+    /// Use it after scene transition or when window regains focus, so input from previous context
+    /// doesn't leak into the new one. `Connected` and `Disconnected` events are kept.
+    ///
+    /// If `emit_releases` is `false`, cached state is cleared immediately and no events are
+    /// generated. If it's `true`, state is left as is and `ButtonReleased`, `ButtonChanged` and
+    /// `AxisChanged` events that bring all pressed buttons and moved axes back to neutral are
+    /// queued instead.
+    pub fn clear(&mut self, emit_releases: bool) {
+        let mut kept = VecDeque::new();
+        while let Some(ev) = self.next_event_priv(false, None) {
+            if matches!(ev.event, EventType::Connected | EventType::Disconnected) {
+                kept.push_back(ev);
+            }
+        }
+        self.events = kept;
+
+        let time = utils::time_now();
+        for i in 0..self.gamepads_data.len() {
+            let id = GamepadId(i);
+            let data = &mut self.gamepads_data[i];
+            data.have_sent_nonzero_for_axis = Default::default();
+            data.dpad_debounce = Default::default();
+            data.stick_dpad = Default::default();
+            data.split_trigger_values = Default::default();
+
+            if !emit_releases {
+                data.socd.reset();
+                data.state.reset();
+                continue;
+            }
+
+            let gamepad = self.gamepad(id);
+            let mut releases = Vec::new();
+            for (nec, data) in gamepad.state().buttons() {
+                if data.is_pressed() || data.value() != 0.0 {
+                    let btn = match gamepad.axis_or_btn_name(nec) {
+                        Some(AxisOrBtn::Btn(b)) => b,
+                        _ => Button::Unknown,
+                    };
+                    if data.is_pressed() {
+                        releases.push(EventType::ButtonReleased(btn, nec));
+                    }
+                    releases.push(EventType::ButtonChanged(btn, 0.0, nec));
+                }
+            }
+            for (nec, data) in gamepad.state().axes() {
+                if data.value() != 0.0 {
+                    let axis = match gamepad.axis_or_btn_name(nec) {
+                        Some(AxisOrBtn::Axis(a)) => a,
+                        _ => Axis::Unknown,
+                    };
+                    releases.push(EventType::AxisChanged(axis, 0.0, nec));
+                }
+            }
+
+            self.events
+                .extend(releases.into_iter().map(|event| Event { id, event, time }));
+        }
+    }
+
     pub(crate) fn ff_sender(&self) -> &Sender<Message> {
         &self.tx
     }
@@ -564,8 +900,11 @@ impl Gilrs {
                 Uuid::from_bytes(gamepad.uuid()),
             )?;
 
-            // We checked if gamepad is connected, so it should never panic
-            let data = &mut self.gamepads_data[gamepad_id];
+            // Custom backend may return gamepad before it sent `Connected` event.
+            let data = self
+                .gamepads_data
+                .get_mut(gamepad_id)
+                .ok_or(MappingError::NotConnected)?;
             data.mapping = mapping;
 
             Ok(s)
@@ -580,7 +919,7 @@ impl Gilrs {
     /// # Errors
     ///
     /// Returns `MappingError::NotSdl2Compatible` if `mapping` have an entry for `Button::{C, Z}`
-    /// or `Axis::{LeftZ, RightZ}`.
+    /// or `Axis::{LeftZ, RightZ}` or one of racing wheel axes.
     pub fn set_mapping_strict<'b, O: Into<Option<&'b str>>>(
         &mut self,
         gamepad_id: usize,
@@ -591,6 +930,9 @@ impl Gilrs {
             || mapping.button(Button::Z).is_some()
             || mapping.axis(Axis::LeftZ).is_some()
             || mapping.axis(Axis::RightZ).is_some()
+            || [Axis::Steering, Axis::Throttle, Axis::Brake, Axis::Clutch]
+                .iter()
+                .any(|&axis| mapping.axis(axis).is_some())
         {
             Err(MappingError::NotSdl2Compatible)
         } else {
@@ -598,6 +940,96 @@ impl Gilrs {
         }
     }
 
+    /// Calibrates analog trigger on given side, so the range of values observed since gamepad was
+    /// connected (or since last [`reset_trigger_calibration()`](Gilrs::reset_trigger_calibration))
+    /// is mapped to full `[0.0, 1.0]` range.
+    ///
+    /// Ask the user to fully pull and release the trigger, then call this function. Returns new
+    /// calibration, so it can be stored and later restored with
+    /// [`set_trigger_calibration()`](Gilrs::set_trigger_calibration). Returns `None` and keeps
+    /// the old calibration if observed range is too small or gamepad with `id` doesn't exist.
+    pub fn calibrate_trigger(&mut self, id: GamepadId, side: Side) -> Option<TriggerCalibration> {
+        let data = self.gamepads_data.get_mut(id.0)?;
+        let i = side_index(side);
+        let calibration = data.trigger_range[i].to_calibration()?;
+        data.profile.triggers[i] = calibration;
+
+        Some(calibration)
+    }
+
+    /// Sets calibration of analog trigger on given side. Does nothing if gamepad with `id` doesn't
+    /// exist.
+    pub fn set_trigger_calibration(
+        &mut self,
+        id: GamepadId,
+        side: Side,
+        calibration: TriggerCalibration,
+    ) {
+        if let Some(data) = self.gamepads_data.get_mut(id.0) {
+            data.profile.triggers[side_index(side)] = calibration;
+        }
+    }
+
+    /// Replaces calibration profile of gamepad, including trigger calibration. Does nothing if
+    /// gamepad with `id` doesn't exist.
+    ///
+    /// Profile is used for values read after this call, cached state is not changed.
+    pub fn apply_profile(&mut self, id: GamepadId, profile: &CalibrationProfile) {
+        if let Some(data) = self.gamepads_data.get_mut(id.0) {
+            data.profile = profile.clone();
+        }
+    }
+
+    /// Enables or disables gamepad. Disabled gamepad doesn't emit any events except `Connected`
+    /// and `Disconnected`, and its force feedback motors are stopped. Its cached state is reset
+    /// when it's disabled. Gamepad stays disabled when it reconnects. Does nothing if gamepad with
+    /// `id` doesn't exist.
+    pub fn set_gamepad_enabled(&mut self, id: GamepadId, enabled: bool) {
+        if let Some(data) = self.gamepads_data.get_mut(id.0) {
+            if data.enabled == enabled {
+                return;
+            }
+
+            data.enabled = enabled;
+            if !enabled {
+                data.state.reset();
+                data.have_sent_nonzero_for_axis = Default::default();
+                data.dpad_debounce = Default::default();
+                data.stick_dpad = Default::default();
+                data.split_trigger_values = Default::default();
+                data.socd.reset();
+            }
+            let _ = self.tx.send(Message::SetEnabled { id: id.0, enabled });
+        }
+    }
+
+    /// Changes how force feedback effects playing on gamepad at the same time are combined.
+    /// Policy is kept when gamepad reconnects. Does nothing if gamepad with `id` doesn't exist.
+    pub fn set_ff_mixing_policy(&mut self, id: GamepadId, policy: MixingPolicy) {
+        if let Some(data) = self.gamepads_data.get_mut(id.0) {
+            data.ff_mixing = policy;
+            let _ = self.tx.send(Message::SetMixingPolicy { id: id.0, policy });
+        }
+    }
+
+    /// Enables or disables [`SocdCleaner`](crate::ev::filter::SocdCleaner) filter for gamepad.
+    /// It's enabled by default. Does nothing if gamepad with `id` doesn't exist.
+    pub fn set_socd_cleaning(&mut self, id: GamepadId, enabled: bool) {
+        if let Some(data) = self.gamepads_data.get_mut(id.0) {
+            data.socd.enabled = enabled;
+        }
+    }
+
+    /// Removes calibration of analog trigger on given side and forgets values observed so far.
+    /// Does nothing if gamepad with `id` doesn't exist.
+    pub fn reset_trigger_calibration(&mut self, id: GamepadId, side: Side) {
+        if let Some(data) = self.gamepads_data.get_mut(id.0) {
+            let i = side_index(side);
+            data.profile.triggers[i] = TriggerCalibration::default();
+            data.trigger_range[i] = ObservedRange::default();
+        }
+    }
+
     pub(crate) fn next_ff_id(&mut self) -> usize {
         // TODO: reuse free ids
         let id = self.next_id;
@@ -613,11 +1045,16 @@ impl Gilrs {
 pub struct GilrsBuilder {
     mappings: MappingDb,
     default_filters: bool,
+    dpad_debounce: DpadDebounce,
     axis_to_btn_pressed: f32,
     axis_to_btn_released: f32,
     update_state: bool,
     env_mappings: bool,
     included_mappings: bool,
+    split_triggers: bool,
+    event_logger: Option<EventLogger>,
+    power_info_interval: Option<Duration>,
+    backend: Option<Box<dyn GamepadBackend>>,
 }
 
 impl GilrsBuilder {
@@ -626,30 +1063,88 @@ impl GilrsBuilder {
         GilrsBuilder {
             mappings: MappingDb::new(),
             default_filters: true,
+            dpad_debounce: DpadDebounce::new(),
             axis_to_btn_pressed: 0.75,
             axis_to_btn_released: 0.65,
             update_state: true,
             env_mappings: true,
             included_mappings: true,
+            split_triggers: true,
+            event_logger: None,
+            power_info_interval: None,
+            backend: None,
         }
     }
 
-    /// If `true`, use [`axis_dpad_to_button`](ev/filter/fn.axis_dpad_to_button.html),
-    /// [`Jitter`](ev/filter/struct.Jitter.html) and [`deadzone`](ev/filter/fn.deadzone.html)
-    /// filters with default parameters. Defaults to `true`.
+    /// If `true`, events returned by `next_event()` go through default filters, which makes them
+    /// ready to use in games. Defaults to `true`.
+    ///
+    /// Default filters are applied in this order:
+    ///
+    /// 1. [`DpadDebounce`](ev/filter/struct.DpadDebounce.html) – stops dpads reported as axes
+    ///    from flickering through center, see [`set_dpad_debounce()`](Self::set_dpad_debounce).
+    /// 2. [`axis_dpad_to_button`](ev/filter/fn.axis_dpad_to_button.html) – dpads reported as
+    ///    axes also emit `DPad*` button events.
+    /// 3. [`Jitter`](ev/filter/struct.Jitter.html) with threshold 0.01 – drops axis events that
+    ///    changed value less than threshold.
+    /// 4. [`deadzone`](ev/filter/fn.deadzone.html) – radial dead zone for sticks (using both
+    ///    axes of stick) and dead zone for other axes, with size reported by device.
+    ///
+    /// Events dropped by filters are skipped. Stick is not emulating dpad, use
+    /// [`StickToDpad`](ev/filter/struct.StickToDpad.html) for that. Triggers always have
+    /// hysteresis between pressed and released state, see
+    /// [`set_axis_to_btn()`](Self::set_axis_to_btn).
+    ///
+    /// Set to `false` to get unfiltered events and build your own filter chain.
     pub fn with_default_filters(mut self, default_filters: bool) -> Self {
         self.default_filters = default_filters;
 
         self
     }
 
-    /// Adds SDL mappings.
+    /// Sets how long dpad reported as axes has to stay centered before the change is returned.
+    /// Only used with default filters. Defaults to 10ms, `Duration::ZERO` disables debouncing.
+    ///
+    /// Moving dpad away from center is never delayed.
+    pub fn set_dpad_debounce(mut self, window: Duration) -> Self {
+        self.dpad_debounce.window = window;
+
+        self
+    }
+
+    /// Sets how often power supply state of gamepads is refreshed in the background. When it
+    /// changes, `EventType::PowerChanged` is emitted and [`Gamepad::power_info()`] returns the
+    /// cached value, so it's cheap to call every frame.
+    ///
+    /// Defaults to 5 seconds, `Duration::ZERO` stops refreshing. Currently only Windows with
+    /// XInput refreshes power information in the background, on other platforms this setting is
+    /// ignored and `PowerChanged` is never emitted. It's also ignored for custom backends.
+    pub fn set_power_info_interval(mut self, interval: Duration) -> Self {
+        self.power_info_interval = Some(interval);
+
+        self
+    }
+
+    /// Adds SDL mappings. Lines that aren't valid mappings are ignored, use
+    /// [`try_add_mappings()`](Self::try_add_mappings) to find out about them.
     pub fn add_mappings(mut self, mappings: &str) -> Self {
         self.mappings.insert(mappings);
 
         self
     }
 
+    /// Adds SDL mappings or returns `MappingError::InvalidSdlMapping` with number of first line
+    /// that can't be parsed. Nothing is added in that case. Empty lines and lines starting with
+    /// `#` are allowed.
+    ///
+    /// Only syntax is checked here. Buttons and axes that gamepad doesn't have are ignored when
+    /// it's connected.
+    pub fn try_add_mappings(self, mappings: &str) -> Result<Self, MappingError> {
+        MappingDb::check(mappings)?;
+
+        Ok(self.add_mappings(mappings))
+    }
+
     /// If true, will add SDL mappings from `SDL_GAMECONTROLLERCONFIG` environment variable.
     /// Defaults to true.
     pub fn add_env_mappings(mut self, env_mappings: bool) -> Self {
@@ -685,6 +1180,43 @@ impl GilrsBuilder {
         self
     }
 
+    /// If true, triggers that device reports on single axis (one on each half of its range) are
+    /// reported as separate `LeftTrigger2` and `RightTrigger2`, same as on other gamepads. Such
+    /// axes are detected from SDL mappings like `lefttrigger:-a2,righttrigger:+a2`, so support
+    /// for a device can be added with [`add_mappings()`](Self::add_mappings). If false, such axis
+    /// is reported as is, with `Axis::Unknown`. Defaults to true.
+    pub fn split_combined_triggers(mut self, split: bool) -> Self {
+        self.split_triggers = split;
+
+        self
+    }
+
+    /// Sets function that is called with gamepad's index and event for every event returned
+    /// from the backend, before default filters are applied. Useful for debugging and tests; it
+    /// doesn't change events returned by `next_event()`.
+    ///
+    /// The function is called on the thread that calls `next_event()`.
+    pub fn set_event_logger<F>(mut self, logger: F) -> Self
+    where
+        F: FnMut(usize, &Event) + Send + 'static,
+    {
+        self.event_logger = Some(EventLogger(Box::new(logger)));
+
+        self
+    }
+
+    /// Uses `backend` instead of the native platform backend, for example to read gamepads over
+    /// network or to replay recorded input in tests. See [`backend`](crate::backend) module for
+    /// details.
+    ///
+    /// Settings that only configure native backends, like
+    /// [`set_power_info_interval()`](Self::set_power_info_interval), have no effect on `backend`.
+    pub fn with_backend<B: GamepadBackend + 'static>(mut self, backend: B) -> Self {
+        self.backend = Some(Box::new(backend));
+
+        self
+    }
+
     /// Creates `Gilrs`.
     pub fn build(mut self) -> Result<Gilrs, Error> {
         if self.included_mappings {
@@ -707,14 +1239,23 @@ impl GilrsBuilder {
         }
 
         let mut is_dummy = false;
-        let inner = match gilrs_core::Gilrs::new() {
-            Ok(g) => g,
-            Err(PlatformError::NotImplemented(g)) => {
-                is_dummy = true;
+        let inner: Box<dyn GamepadBackend> = match self.backend.take() {
+            Some(backend) => backend,
+            None => match gilrs_core::Gilrs::new() {
+                Ok(mut g) => {
+                    if let Some(interval) = self.power_info_interval {
+                        g.set_power_info_interval(interval);
+                    }
 
-                g
-            }
-            Err(PlatformError::Other(e)) => return Err(Error::Other(e)),
+                    Box::new(g)
+                }
+                Err(PlatformError::NotImplemented(g)) => {
+                    is_dummy = true;
+
+                    Box::new(g)
+                }
+                Err(PlatformError::Other(e)) => return Err(Error::Other(e)),
+            },
         };
 
         let (tx, rx) = server::init();
@@ -727,9 +1268,12 @@ impl GilrsBuilder {
             counter: 0,
             mappings: self.mappings,
             default_filters: self.default_filters,
+            dpad_debounce: self.dpad_debounce,
             events: VecDeque::new(),
             axis_to_btn_pressed: self.axis_to_btn_pressed,
             axis_to_btn_released: self.axis_to_btn_released,
+            split_triggers: self.split_triggers,
+            event_logger: self.event_logger,
             update_state: self.update_state,
             gamepads_data: Vec::new(),
         };
@@ -749,6 +1293,16 @@ impl Default for GilrsBuilder {
     }
 }
 
+type EventLoggerFn = dyn FnMut(usize, &Event) + Send;
+
+struct EventLogger(Box<EventLoggerFn>);
+
+impl fmt::Debug for EventLogger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EventLogger")
+    }
+}
+
 /// Iterator over all connected gamepads.
 pub struct ConnectedGamepadsIterator<'a>(&'a Gilrs, usize);
 
@@ -772,6 +1326,15 @@ impl<'a> Iterator for ConnectedGamepadsIterator<'a> {
     }
 }
 
+impl<'a> IntoIterator for &'a Gilrs {
+    type Item = (GamepadId, Gamepad<'a>);
+    type IntoIter = ConnectedGamepadsIterator<'a>;
+
+    fn into_iter(self) -> ConnectedGamepadsIterator<'a> {
+        self.gamepads()
+    }
+}
+
 /// Represents handle to game controller.
 ///
 /// Using this struct you can access cached gamepad state, information about gamepad such as name
@@ -779,7 +1342,7 @@ impl<'a> Iterator for ConnectedGamepadsIterator<'a> {
 #[derive(Debug, Copy, Clone)]
 pub struct Gamepad<'a> {
     data: &'a GamepadData,
-    inner: &'a gilrs_core::Gamepad,
+    inner: &'a dyn BackendGamepad,
 }
 
 impl<'a> Gamepad<'a> {
@@ -805,6 +1368,9 @@ impl<'a> Gamepad<'a> {
 
     /// Returns gamepad's UUID.
     ///
+    /// UUID has the same layout as SDL2 joystick GUID, so it can be used to look up mappings in
+    /// `gamecontrollerdb.txt`. See [`gilrs_core::Gamepad::uuid()`] for details.
+    ///
     /// It is recommended to process with the [UUID crate](https://crates.io/crates/uuid).
     /// Use `Uuid::from_bytes` method to create a `Uuid` from the returned bytes.
     pub fn uuid(&self) -> [u8; 16] {
@@ -827,6 +1393,20 @@ impl<'a> Gamepad<'a> {
         self.inner.product_id()
     }
 
+    /// Returns family of the device, useful for showing matching button glyphs.
+    ///
+    /// Brand is based on vendor ID. If it's not available (for example XInput on Windows), name
+    /// of the device is used and `Brand::Generic` is returned if it's not recognized either.
+    pub fn brand(&self) -> Brand {
+        Brand::detect(self.vendor_id(), self.os_name())
+    }
+
+    /// Returns the product version (hardware or firmware revision), when available. XInput backend
+    /// can't read it.
+    pub fn version(&self) -> Option<u16> {
+        self.inner.version()
+    }
+
     /// Returns cached gamepad state.
     pub fn state(&self) -> &GamepadState {
         &self.data.state
@@ -837,6 +1417,43 @@ impl<'a> Gamepad<'a> {
         self.inner.is_connected()
     }
 
+    /// Returns why gamepad was disconnected, or `None` if it is connected or wasn't connected since
+    /// `Gilrs` was created.
+    ///
+    /// This can be used to decide whether to wait for the gamepad to come back or to tell the user
+    /// that something went wrong.
+    pub fn disconnect_reason(&self) -> Option<DisconnectReason> {
+        if self.is_connected() {
+            None
+        } else {
+            self.data.disconnect_reason
+        }
+    }
+
+    /// Returns time of the most recent button or axis change. `None` if gamepad didn't report any
+    /// input yet.
+    pub fn last_input_time(&self) -> Option<SystemTime> {
+        self.data.state.last_input_time()
+    }
+
+    /// Returns how long ago gamepad reported button or axis change. `None` if gamepad didn't
+    /// report any input yet.
+    ///
+    /// ```
+    /// # use gilrs::Gilrs;
+    /// # use std::time::Duration;
+    /// # let gilrs = Gilrs::new().unwrap();
+    /// let is_idle = gilrs.gamepads().all(|(_, gamepad)| {
+    ///     gamepad
+    ///         .idle_duration()
+    ///         .map_or(true, |idle| idle > Duration::from_secs(60))
+    /// });
+    /// ```
+    pub fn idle_duration(&self) -> Option<Duration> {
+        self.last_input_time()
+            .map(|time| utils::time_now().duration_since(time).unwrap_or_default())
+    }
+
     /// Examines cached gamepad state to check if given button is pressed. Panics if `btn` is
     /// `Unknown`.
     ///
@@ -847,6 +1464,42 @@ impl<'a> Gamepad<'a> {
         self.data.is_pressed(btn)
     }
 
+    /// Examines cached gamepad state to check if given button was pressed since last call to
+    /// [`Gilrs::inc()`]. Panics if `btn` is `Unknown`.
+    ///
+    /// ```
+    /// use gilrs::Button;
+    /// # let mut gilrs = gilrs::Gilrs::new().unwrap();
+    ///
+    /// loop {
+    ///     while let Some(_) = gilrs.next_event() {}
+    ///
+    ///     for (_, gamepad) in gilrs.gamepads() {
+    ///         if gamepad.just_pressed(Button::South) {
+    ///             println!("Jump!");
+    ///         }
+    ///     }
+    ///
+    ///     gilrs.inc();
+    ///     # break;
+    /// }
+    /// ```
+    pub fn just_pressed(&self, btn: Button) -> bool {
+        self.data
+            .button_code_or_nec(btn)
+            .map(|nec| self.data.state.just_pressed(nec))
+            .unwrap_or(false)
+    }
+
+    /// Examines cached gamepad state to check if given button was released since last call to
+    /// [`Gilrs::inc()`]. Panics if `btn` is `Unknown`.
+    pub fn just_released(&self, btn: Button) -> bool {
+        self.data
+            .button_code_or_nec(btn)
+            .map(|nec| self.data.state.just_released(nec))
+            .unwrap_or(false)
+    }
+
     /// Examines cached gamepad state to check axis's value. Panics if `axis` is `Unknown`.
     ///
     /// If you know `Code` of the element that you want to examine, it's recommended to use methods
@@ -870,20 +1523,107 @@ impl<'a> Gamepad<'a> {
     /// If you know `Code` of the element that you want to examine, it's recommended to use methods
     /// directly on `State`, because this version have to check which `Code` is mapped to element of
     /// gamepad.
+    ///
+    /// Together with [`axis_code()`](Self::axis_code), returned data can be used to show both
+    /// value reported by the device ([`AxisData::raw_value()`]) and value after mapping and
+    /// filters ([`AxisData::value()`]), for example on calibration screen.
     pub fn axis_data(&self, axis: Axis) -> Option<&AxisData> {
         self.data.axis_data(axis)
     }
 
-    /// Returns device's power supply state. See [`PowerInfo`](enum.PowerInfo.html) for details.
-    pub fn power_info(&self) -> PowerInfo {
-        self.inner.power_info()
-    }
-
-    /// Returns source of gamepad mapping. Can be used to filter gamepads which do not provide
-    /// unified controller layout.
+    /// Examines cached gamepad state to get position of stick on given side as `(x, y)`.
     ///
     /// ```
-    /// use gilrs::MappingSource;
+    /// use gilrs::Side;
+    /// # let gilrs = gilrs::Gilrs::new().unwrap();
+    ///
+    /// for (_, gamepad) in gilrs.gamepads() {
+    ///     let (move_x, move_y) = gamepad.stick(Side::Left);
+    ///     let (look_x, look_y) = gamepad.stick(Side::Right);
+    /// }
+    /// ```
+    pub fn stick(&self, side: Side) -> (f32, f32) {
+        let (x, y) = side.stick_axes();
+        (self.value(x), self.value(y))
+    }
+
+    /// Returns how far stick on given side is from the center, in range \[0.0, 1.0\]. Uses the
+    /// same cached values as [`stick()`](Self::stick), so dead zone is already applied.
+    pub fn stick_magnitude(&self, side: Side) -> f32 {
+        let (x, y) = self.stick(side);
+        x.hypot(y).min(1.0)
+    }
+
+    /// Returns direction of stick on given side in radians, in range \[-π, π\]. 0.0 is right and
+    /// π/2 is up. Returns 0.0 if stick is centered (its magnitude is 0.0).
+    pub fn stick_angle(&self, side: Side) -> f32 {
+        let (x, y) = self.stick(side);
+        if x == 0.0 && y == 0.0 {
+            0.0
+        } else {
+            y.atan2(x)
+        }
+    }
+
+    /// Examines cached gamepad state to get value of analog trigger (`LeftTrigger2` or
+    /// `RightTrigger2`) on given side. Returns 0.0 if trigger didn't report any value yet.
+    pub fn trigger(&self, side: Side) -> f32 {
+        self.button_data(side.trigger())
+            .map(|data| data.value())
+            .unwrap_or(0.0)
+    }
+
+    /// Examines cached gamepad state to check if shoulder button (`LeftTrigger` or
+    /// `RightTrigger`) on given side is pressed.
+    pub fn shoulder(&self, side: Side) -> bool {
+        self.is_pressed(side.shoulder())
+    }
+
+    /// Returns calibration of analog trigger on given side.
+    pub fn trigger_calibration(&self, side: Side) -> TriggerCalibration {
+        self.data.profile.triggers[side_index(side)]
+    }
+
+    /// Returns calibration profile of this gamepad. See
+    /// [`Gilrs::apply_profile()`](Gilrs::apply_profile).
+    pub fn current_profile(&self) -> &CalibrationProfile {
+        &self.data.profile
+    }
+
+    /// Lights player indicator LED matching `index` (0 for first player), or turns all of them
+    /// off if `index` is `None`.
+    ///
+    /// Returns `Error::NotSupported` if the LEDs can't be controlled or there is no LED for
+    /// `index`. Currently it only works on Linux, for Xbox 360 compatible controllers handled by
+    /// the xpad driver. Writing to the LED usually requires udev rule that gives user access to
+    /// it, otherwise `Error::Other` is returned.
+    #[allow(clippy::result_large_err)]
+    pub fn set_player_index(&self, index: Option<u8>) -> Result<(), Error> {
+        if !self.is_connected() {
+            return Err(Error::NotSupported);
+        }
+
+        self.inner
+            .set_player_index(index)
+            .map_err(|e| match e.kind() {
+                io::ErrorKind::Unsupported => Error::NotSupported,
+                _ => Error::Other(Box::new(e)),
+            })
+    }
+
+    /// Returns device's power supply state. See [`PowerInfo`](enum.PowerInfo.html) for details.
+    ///
+    /// On some platforms the value is refreshed in the background, see
+    /// [`GilrsBuilder::set_power_info_interval()`].
+    pub fn power_info(&self) -> PowerInfo {
+        self.inner.power_info()
+    }
+
+    /// Returns source of gamepad mapping. Can be used to filter gamepads which do not provide
+    /// unified controller layout.
+    ///
+    /// ```
+    /// use gilrs::MappingSource;
     /// # let mut gilrs = gilrs::Gilrs::new().unwrap();
     ///
     /// for (_, gamepad) in gilrs.gamepads().filter(
@@ -893,19 +1633,107 @@ impl<'a> Gamepad<'a> {
     /// }
     /// ```
     pub fn mapping_source(&self) -> MappingSource {
-        if self.data.mapping.is_default() {
-            // TODO: check if it's Driver or None
+        if !self.data.mapping.is_default() {
+            MappingSource::SdlMappings
+        } else if self
+            .inner
+            .buttons()
+            .contains(&gilrs_core::native_ev_codes::BTN_SOUTH)
+        {
+            // Driver reports gamepad buttons, not just numbered joystick buttons.
             MappingSource::Driver
         } else {
-            MappingSource::SdlMappings
+            MappingSource::None
         }
     }
 
+    /// Returns true if gamepad uses SDL mapping or its driver provides unified controller layout.
+    /// Otherwise buttons and axes are probably `Unknown` or mislabeled and user should be asked to
+    /// map them, see [`Gilrs::set_mapping()`](Gilrs::set_mapping).
+    ///
+    /// It's always true for XInput gamepads.
+    pub fn is_known(&self) -> bool {
+        self.mapping_source() != MappingSource::None
+    }
+
+    /// Returns false if gamepad was disabled with
+    /// [`Gilrs::set_gamepad_enabled()`](Gilrs::set_gamepad_enabled).
+    pub fn is_enabled(&self) -> bool {
+        self.data.enabled
+    }
+
+    /// Returns policy set with
+    /// [`Gilrs::set_ff_mixing_policy()`](Gilrs::set_ff_mixing_policy).
+    pub fn ff_mixing_policy(&self) -> MixingPolicy {
+        self.data.ff_mixing
+    }
+
     /// Returns true if force feedback is supported by device.
     pub fn is_ff_supported(&self) -> bool {
         self.inner.is_ff_supported()
     }
 
+    /// Returns how many force feedback effects can exist for this gamepad at the same time, or
+    /// `None` if their number isn't limited.
+    ///
+    /// Effects are mixed by gilrs and device only receives strength of its motors, so this
+    /// currently always returns `None`.
+    pub fn ff_slots_total(&self) -> Option<usize> {
+        None
+    }
+
+    /// Returns how many more force feedback effects can be created for this gamepad, or `None` if
+    /// their number isn't limited. If it's `Some(0)`, [`EffectBuilder::finish()`] returns
+    /// [`ff::Error::NoFreeSlots`].
+    ///
+    /// Like [`ff_slots_total()`](Gamepad::ff_slots_total), it currently always returns `None`.
+    ///
+    /// [`EffectBuilder::finish()`]: crate::ff::EffectBuilder::finish
+    /// [`ff::Error::NoFreeSlots`]: crate::ff::Error::NoFreeSlots
+    pub fn ff_slots_free(&self) -> Option<usize> {
+        None
+    }
+
+    /// Returns features of the device. They are queried once, when gamepad is connected.
+    ///
+    /// ```
+    /// # let gilrs = gilrs::Gilrs::new().unwrap();
+    /// for (_, gamepad) in gilrs.gamepads() {
+    ///     let caps = gamepad.capabilities();
+    ///     println!("{}: rumble: {}, subtype: {:?}", gamepad.name(), caps.has_rumble, caps.subtype);
+    /// }
+    /// ```
+    pub fn capabilities(&self) -> Capabilities {
+        self.data.capabilities
+    }
+
+    /// Returns true if gamepad has separate rumble motors in triggers, like Xbox One and newer
+    /// controllers. Currently it can only be true on Windows with Windows Gaming Input.
+    ///
+    /// Uses capabilities queried when gamepad was connected. Always returns false for disconnected
+    /// gamepads. Force feedback effects never use trigger motors, so effects play the same way on
+    /// gamepads without them.
+    pub fn has_trigger_rumble(&self) -> bool {
+        self.is_connected() && self.data.capabilities.has_trigger_rumble
+    }
+
+    /// Returns true if gamepad can play effects of given kind.
+    ///
+    /// Uses capabilities queried when gamepad was connected, so it's cheap to call. Always
+    /// returns false for disconnected gamepads and gamepads without rumble motors.
+    pub fn supports_effect(&self, kind: &BaseEffectType) -> bool {
+        if !self.is_connected() || !self.data.capabilities.has_rumble {
+            return false;
+        }
+
+        match kind {
+            BaseEffectType::Weak { .. }
+            | BaseEffectType::Strong { .. }
+            | BaseEffectType::WeakRamp { .. }
+            | BaseEffectType::StrongRamp { .. } => true,
+        }
+    }
+
     /// Change gamepad position used by force feedback effects.
     pub fn set_listener_position<Vec3: Into<[f32; 3]>>(
         &self,
@@ -924,6 +1752,47 @@ impl<'a> Gamepad<'a> {
         }
     }
 
+    /// Sets speed of strong (left, low frequency) and weak (right, high frequency) rumble motor.
+    ///
+    /// Values are sent to device until they are changed again. While any of them is non-zero,
+    /// they replace output of force feedback effects playing on this gamepad. Set both to 0 to
+    /// give control back to effects.
+    pub fn set_rumble_motors(&self, strong: u16, weak: u16) -> Result<(), FfError> {
+        if !self.is_connected() {
+            Err(FfError::Disconnected(self.id()))
+        } else if !self.is_ff_supported() {
+            Err(FfError::FfNotSupported(self.id()))
+        } else {
+            self.data.tx.send(Message::SetMotors {
+                id: self.data.id.0,
+                strong,
+                weak,
+            })?;
+            Ok(())
+        }
+    }
+
+    /// Rumbles gamepad once with given motor speeds for `duration`.
+    ///
+    /// Rumble is added to output of force feedback effects that are playing at the same time if
+    /// gamepad uses [`MixingPolicy::Sum`], otherwise each motor uses the stronger of them.
+    /// Calling it again before `duration` passes replaces previous rumble.
+    pub fn rumble(&self, strong: u16, weak: u16, duration: Duration) -> Result<(), FfError> {
+        if !self.is_connected() {
+            Err(FfError::Disconnected(self.id()))
+        } else if !self.is_ff_supported() {
+            Err(FfError::FfNotSupported(self.id()))
+        } else {
+            self.data.tx.send(Message::Rumble {
+                id: self.data.id.0,
+                strong,
+                weak,
+                duration: duration.into(),
+            })?;
+            Ok(())
+        }
+    }
+
     /// Returns `AxisOrBtn` mapped to `Code`.
     pub fn axis_or_btn_name(&self, ec: Code) -> Option<AxisOrBtn> {
         self.data.axis_or_btn_name(ec)
@@ -939,8 +1808,45 @@ impl<'a> Gamepad<'a> {
         self.data.axis_code(axis)
     }
 
-    /// Returns area in which axis events should be ignored.
+    /// Returns axes reported by the device with their codes. Axes mapped to buttons (like analog
+    /// triggers) are skipped and axes without mapping are listed as `Axis::Unknown`.
+    ///
+    /// ```
+    /// # let gilrs = gilrs::Gilrs::new().unwrap();
+    /// for (_, gamepad) in gilrs.gamepads() {
+    ///     for (axis, code) in gamepad.axes() {
+    ///         println!("{} ({})", axis, code);
+    ///     }
+    /// }
+    /// ```
+    pub fn axes(&self) -> impl Iterator<Item = (Axis, Code)> + 'a {
+        let mapping = &self.data.mapping;
+        self.inner.axes().iter().filter_map(move |&nec| {
+            match mapping.map(&nec) {
+                Some(AxisOrBtn::Axis(axis)) => Some((axis, Code(nec))),
+                Some(AxisOrBtn::Btn(_)) => None,
+                // Both halves of this axis are reported as triggers.
+                None if mapping.combined_triggers().map(|t| t.code) == Some(nec) => None,
+                None => Some((Axis::Unknown, Code(nec))),
+            }
+        })
+    }
+
+    /// Returns area in which axis events should be ignored. Dead zone from
+    /// [`current_profile()`](Self::current_profile) takes precedence over the one reported by
+    /// device.
     pub fn deadzone(&self, axis: Code) -> Option<f32> {
+        let profile = &self.data.profile;
+        let deadzone = match self.axis_or_btn_name(axis) {
+            Some(AxisOrBtn::Axis(a)) => profile.deadzones.get(&a).copied(),
+            Some(AxisOrBtn::Btn(Button::LeftTrigger2)) => profile.trigger_deadzones[0],
+            Some(AxisOrBtn::Btn(Button::RightTrigger2)) => profile.trigger_deadzones[1],
+            _ => None,
+        };
+        if deadzone.is_some() {
+            return deadzone;
+        }
+
         self.inner.axis_info(axis.0).map(|i| {
             let range = i.max as f32 - i.min as f32;
 
@@ -972,13 +1878,28 @@ pub(crate) struct GamepadData {
     id: GamepadId,
     // Flags used by the deadzone filter.
     pub(crate) have_sent_nonzero_for_axis: [bool; 6],
+    // DPadX and DPadY state used by the dpad debounce filter.
+    pub(crate) dpad_debounce: [DebounceState; 2],
+    // Right, left, up and down pressed by `StickToDpad` filter for left and right stick.
+    pub(crate) stick_dpad: [[bool; 4]; 2],
+    pub(crate) socd: SocdState,
+    enabled: bool,
+    ff_mixing: MixingPolicy,
+    profile: CalibrationProfile,
+    // Left and right analog trigger.
+    trigger_range: [ObservedRange; 2],
+    // Last values of left and right trigger split from combined axis.
+    split_trigger_values: [f32; 2],
+    disconnect_reason: Option<DisconnectReason>,
+    capabilities: Capabilities,
+    uuid: Uuid,
 }
 
 impl GamepadData {
     fn new(
         id: GamepadId,
         tx: Sender<Message>,
-        gamepad: &gilrs_core::Gamepad,
+        gamepad: &dyn BackendGamepad,
         db: &MappingDb,
     ) -> Self {
         let mapping = db
@@ -1011,9 +1932,32 @@ impl GamepadData {
             tx,
             id,
             have_sent_nonzero_for_axis: Default::default(),
+            dpad_debounce: Default::default(),
+            stick_dpad: Default::default(),
+            socd: Default::default(),
+            enabled: true,
+            ff_mixing: MixingPolicy::default(),
+            profile: Default::default(),
+            trigger_range: Default::default(),
+            split_trigger_values: Default::default(),
+            disconnect_reason: None,
+            capabilities: gamepad.capabilities(),
+            uuid: Uuid::from_bytes(gamepad.uuid()),
         }
     }
 
+    /// Records and calibrates value of analog trigger. Other buttons are returned unchanged.
+    fn calibrate_trigger(&mut self, btn: Button, val: f32) -> f32 {
+        let i = match btn {
+            Button::LeftTrigger2 => side_index(Side::Left),
+            Button::RightTrigger2 => side_index(Side::Right),
+            _ => return val,
+        };
+        self.trigger_range[i].observe(val);
+
+        self.profile.triggers[i].apply(val)
+    }
+
     /// if `mapping_source()` is `SdlMappings` returns the name of the mapping used by the gamepad.
     /// Otherwise returns `None`.
     ///
@@ -1034,14 +1978,17 @@ impl GamepadData {
     /// directly on `State`, because this version have to check which `Code` is mapped to element of
     /// gamepad.
     pub fn is_pressed(&self, btn: Button) -> bool {
-        assert_ne!(btn, Button::Unknown);
-
-        self.button_code(btn)
-            .or_else(|| btn.to_nec())
+        self.button_code_or_nec(btn)
             .map(|nec| self.state.is_pressed(nec))
             .unwrap_or(false)
     }
 
+    fn button_code_or_nec(&self, btn: Button) -> Option<Code> {
+        assert_ne!(btn, Button::Unknown);
+
+        self.button_code(btn).or_else(|| Code::from_button(btn))
+    }
+
     /// Examines cached gamepad state to check axis's value. Panics if `axis` is `Unknown`.
     ///
     /// If you know `Code` of the element that you want to examine, it's recommended to use methods
@@ -1103,6 +2050,51 @@ pub enum MappingSource {
     None,
 }
 
+/// Family of gamepad, see [`Gamepad::brand()`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum Brand {
+    Xbox,
+    PlayStation,
+    Nintendo,
+    /// Other vendors, including third party controllers.
+    Generic,
+}
+
+impl Brand {
+    const MICROSOFT: u16 = 0x045e;
+    const SONY: u16 = 0x054c;
+    const NINTENDO: u16 = 0x057e;
+
+    pub(crate) fn detect(vendor_id: Option<u16>, name: &str) -> Self {
+        match vendor_id {
+            Some(Self::MICROSOFT) => Brand::Xbox,
+            Some(Self::SONY) => Brand::PlayStation,
+            Some(Self::NINTENDO) => Brand::Nintendo,
+            Some(_) => Brand::Generic,
+            None => {
+                let name = name.to_lowercase();
+                if name.contains("xbox") {
+                    Brand::Xbox
+                } else if ["playstation", "dualshock", "dualsense"]
+                    .iter()
+                    .any(|n| name.contains(n))
+                {
+                    Brand::PlayStation
+                } else if ["nintendo", "joy-con", "switch"]
+                    .iter()
+                    .any(|n| name.contains(n))
+                {
+                    Brand::Nintendo
+                } else {
+                    Brand::Generic
+                }
+            }
+        }
+    }
+}
+
 /// Gamepad ID.
 ///
 /// It's not possible to create instance of this type directly, but you can obtain one from Gamepad
@@ -1123,6 +2115,13 @@ impl Display for GamepadId {
     }
 }
 
+fn side_index(side: Side) -> usize {
+    match side {
+        Side::Left => 0,
+        Side::Right => 1,
+    }
+}
+
 fn axis_value(info: &AxisInfo, val: i32, axis: Axis) -> f32 {
     let mut range = info.max as f32 - info.min as f32;
     let mut val = val as f32 - info.min as f32;
@@ -1146,18 +2145,19 @@ fn axis_value(info: &AxisInfo, val: i32, axis: Axis) -> f32 {
         val = -val;
     }
 
-    utils::clamp(val, -1.0, 1.0)
+    // Broken `AxisInfo` (for example `min == max`) can give us NaN or infinity here.
+    utils::clamp_finite(val, -1.0, 1.0)
 }
 
 fn btn_value(info: &AxisInfo, val: i32) -> f32 {
-    let range = (info.max - info.min) as f32;
-    let mut val = (val - info.min) as f32;
-    val /= range;
+    // Subtract as floats, `max - min` can overflow i32.
+    let range = info.max as f32 - info.min as f32;
+    let val = val as f32 - info.min as f32;
 
-    utils::clamp(val, 0.0, 1.0)
+    utils::clamp_finite(val / range, 0.0, 1.0)
 }
 
-/// Error type which can be returned when creating `Gilrs`.
+/// Error type which can be returned when creating `Gilrs` or controlling gamepad.
 #[derive(Debug)]
 pub enum Error {
     /// Gilrs does not support current platform, but you can use dummy context from this error if
@@ -1165,6 +2165,8 @@ pub enum Error {
     NotImplemented(Gilrs),
     /// Either `pressed ≤ released` or one of values is outside [0.0, 1.0] range.
     InvalidAxisToBtn,
+    /// Gamepad or platform doesn't support requested operation.
+    NotSupported,
     /// Platform specific error.
     Other(Box<dyn error::Error + Send + Sync + 'static>),
 }
@@ -1176,6 +2178,7 @@ impl Display for Error {
             Error::InvalidAxisToBtn => f.write_str(
                 "Either `pressed ≤ released` or one of values is outside [0.0, 1.0] range.",
             ),
+            Error::NotSupported => f.write_str("Operation is not supported by the gamepad."),
             Error::Other(ref e) => e.fmt(f),
         }
     }
@@ -1199,7 +2202,1050 @@ const _: () = {
 
 #[cfg(test)]
 mod tests {
-    use super::{axis_value, Axis, AxisInfo};
+    use super::{
+        axis_value, btn_value, Axis, AxisInfo, Brand, Code, DisconnectReason, Error, Event,
+        EventType, GamepadId, Gilrs, GilrsBuilder, MappingSource, PowerInfo,
+    };
+    use crate::ev::filter::{SocdCleaner, SocdMode, StickToDpad};
+    use crate::ff::{BaseEffectType, EffectBuilder, Error as FfError};
+    use crate::{Button, Side};
+    use crate::{CalibrationProfile, Filter, MappingError};
+    use gilrs_core::backend::{BackendGamepad, GamepadBackend};
+    use gilrs_core::{
+        native_ev_codes as nec, EvCode, Event as RawEvent, EventType as RawEventType,
+    };
+    use std::collections::VecDeque;
+    use std::sync::mpsc::{self, Receiver, TryRecvError};
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::{Duration, SystemTime};
+
+    static STICK_INFO: AxisInfo = AxisInfo {
+        min: -128,
+        max: 127,
+        deadzone: None,
+    };
+
+    #[derive(Debug)]
+    struct MockGamepad;
+
+    impl BackendGamepad for MockGamepad {
+        fn name(&self) -> &str {
+            "Mock"
+        }
+
+        fn is_connected(&self) -> bool {
+            true
+        }
+
+        fn uuid(&self) -> [u8; 16] {
+            [0; 16]
+        }
+
+        fn buttons(&self) -> &[EvCode] {
+            &[nec::BTN_SOUTH, nec::BTN_LTHUMB, nec::BTN_LT2, nec::BTN_RT2]
+        }
+
+        fn axes(&self) -> &[EvCode] {
+            &[nec::AXIS_LSTICKX, nec::AXIS_LSTICKY]
+        }
+
+        fn axis_info(&self, nec: EvCode) -> Option<&AxisInfo> {
+            if nec == nec::AXIS_LSTICKX || nec == nec::AXIS_LSTICKY {
+                Some(&STICK_INFO)
+            } else {
+                None
+            }
+        }
+    }
+
+    #[derive(Debug)]
+    struct MockBackend {
+        gamepad: MockGamepad,
+        events: VecDeque<RawEvent>,
+    }
+
+    impl GamepadBackend for MockBackend {
+        fn next_event(&mut self) -> Option<RawEvent> {
+            self.events.pop_front()
+        }
+
+        fn next_event_blocking(&mut self, _timeout: Option<Duration>) -> Option<RawEvent> {
+            self.events.pop_front()
+        }
+
+        fn gamepad(&self, id: usize) -> Option<&dyn BackendGamepad> {
+            if id == 0 {
+                Some(&self.gamepad)
+            } else {
+                None
+            }
+        }
+
+        fn last_gamepad_hint(&self) -> usize {
+            1
+        }
+    }
+
+    /// Returns builder with mock backend that emits `events` for gamepad 0, without any mappings
+    /// other than `mappings`.
+    fn mock_builder(events: &[RawEventType], mappings: &str) -> GilrsBuilder {
+        GilrsBuilder::new()
+            .add_included_mappings(false)
+            .add_env_mappings(false)
+            .add_mappings(mappings)
+            .with_backend(MockBackend {
+                gamepad: MockGamepad,
+                events: events.iter().map(|&ev| RawEvent::new(0, ev)).collect(),
+            })
+    }
+
+    fn mock_gilrs(events: &[RawEventType], mappings: &str) -> Gilrs {
+        mock_builder(events, mappings).build().unwrap()
+    }
+
+    /// Backend that reads events from a thread, like native backends do.
+    #[derive(Debug)]
+    struct ThreadBackend {
+        gamepad: MockGamepad,
+        rx: Receiver<RawEvent>,
+        alive: bool,
+    }
+
+    impl GamepadBackend for ThreadBackend {
+        fn next_event(&mut self) -> Option<RawEvent> {
+            match self.rx.try_recv() {
+                Ok(ev) => Some(ev),
+                Err(TryRecvError::Empty) => None,
+                Err(TryRecvError::Disconnected) => {
+                    self.alive = false;
+                    None
+                }
+            }
+        }
+
+        fn next_event_blocking(&mut self, _timeout: Option<Duration>) -> Option<RawEvent> {
+            self.next_event()
+        }
+
+        fn gamepad(&self, id: usize) -> Option<&dyn BackendGamepad> {
+            if id == 0 {
+                Some(&self.gamepad)
+            } else {
+                None
+            }
+        }
+
+        fn last_gamepad_hint(&self) -> usize {
+            1
+        }
+
+        fn is_alive(&self) -> bool {
+            self.alive
+        }
+    }
+
+    #[test]
+    fn dead_event_thread() {
+        let (tx, rx) = mpsc::channel();
+        let thread = thread::spawn(move || {
+            tx.send(RawEvent::new(
+                0,
+                RawEventType::ButtonPressed(nec::BTN_SOUTH),
+            ))
+            .unwrap();
+            panic!("event thread failed");
+        });
+        assert!(thread.join().is_err());
+
+        let mut gilrs = GilrsBuilder::new()
+            .add_included_mappings(false)
+            .add_env_mappings(false)
+            .with_backend(ThreadBackend {
+                gamepad: MockGamepad,
+                rx,
+                alive: true,
+            })
+            .build()
+            .unwrap();
+
+        assert!(gilrs.is_alive());
+        assert!(matches!(
+            gilrs.next_event().map(|ev| ev.event),
+            Some(EventType::ButtonPressed(Button::South, _))
+        ));
+        // ButtonChanged
+        assert!(gilrs.next_event().is_some());
+        assert!(gilrs.next_event().is_none());
+        assert!(!gilrs.is_alive());
+    }
+
+    #[test]
+    fn invalid_id_is_ignored() {
+        let mut gilrs = mock_gilrs(&[], "");
+        let id = GamepadId(5);
+
+        assert!(gilrs.connected_gamepad(id).is_none());
+        assert!(gilrs.get_gamepad(id).is_none());
+        assert!(gilrs.get_gamepad(GamepadId(0)).is_some());
+        assert_eq!(gilrs.calibrate_trigger(id, Side::Left), None);
+        gilrs.set_trigger_calibration(id, Side::Left, Default::default());
+        gilrs.reset_trigger_calibration(id, Side::Right);
+        gilrs.apply_profile(id, &CalibrationProfile::default());
+        gilrs.set_gamepad_enabled(id, false);
+        gilrs.set_socd_cleaning(id, false);
+        assert_eq!(
+            gilrs.set_mapping(5, &Default::default(), None),
+            Err(MappingError::NotConnected)
+        );
+        assert!(matches!(
+            EffectBuilder::new().gamepads(&[id]).finish(&mut gilrs),
+            Err(FfError::Disconnected(_))
+        ));
+
+        // Valid gamepad is not affected.
+        let gamepad = gilrs.gamepad(GamepadId(0));
+        assert!(gamepad.is_enabled());
+        assert_eq!(gamepad.current_profile(), &CalibrationProfile::default());
+    }
+
+    #[test]
+    fn is_known() {
+        let gilrs = mock_gilrs(&[], "");
+        let gamepad = gilrs.gamepad(GamepadId(0));
+        assert_eq!(gamepad.mapping_source(), MappingSource::Driver);
+        assert!(gamepad.is_known());
+
+        let gilrs = mock_gilrs(&[], "00000000000000000000000000000000,Mock,a:b0,");
+        let gamepad = gilrs.gamepad(GamepadId(0));
+        assert_eq!(gamepad.mapping_source(), MappingSource::SdlMappings);
+        assert!(gamepad.is_known());
+    }
+
+    #[test]
+    fn list_axes() {
+        let build = |mappings| mock_gilrs(&[], mappings);
+        let (x, y) = (Code(nec::AXIS_LSTICKX), Code(nec::AXIS_LSTICKY));
+
+        let gilrs = build("");
+        let axes: Vec<_> = gilrs.gamepad(GamepadId(0)).axes().collect();
+        assert_eq!(axes, [(Axis::LeftStickX, x), (Axis::LeftStickY, y)]);
+
+        let gilrs = build("00000000000000000000000000000000,Mock,steering:a0,");
+        let axes: Vec<_> = gilrs.gamepad(GamepadId(0)).axes().collect();
+        assert_eq!(axes, [(Axis::Steering, x), (Axis::Unknown, y)]);
+
+        let gilrs = build("00000000000000000000000000000000,Mock,throttle:a1,lefttrigger:a0,");
+        let axes: Vec<_> = gilrs.gamepad(GamepadId(0)).axes().collect();
+        assert_eq!(axes, [(Axis::Throttle, y)]);
+    }
+
+    #[test]
+    fn iterate_gilrs() {
+        let gilrs = mock_gilrs(&[], "");
+
+        assert_eq!(gilrs.len(), 1);
+        assert!(!gilrs.is_empty());
+        let mut ids = Vec::new();
+        for (id, gamepad) in &gilrs {
+            assert_eq!(gamepad.name(), "Mock");
+            ids.push(id);
+        }
+        assert_eq!(ids, [GamepadId(0)]);
+    }
+
+    #[test]
+    fn supports_effect_without_motors() {
+        let gilrs = mock_gilrs(&[], "");
+
+        let gamepad = gilrs.gamepad(GamepadId(0));
+        assert!(gamepad.is_connected());
+        assert!(!gamepad.supports_effect(&BaseEffectType::Strong { magnitude: 60_000 }));
+        assert!(!gamepad.supports_effect(&BaseEffectType::WeakRamp { start: 0, end: 1 }));
+    }
+
+    #[test]
+    fn dpad_debounce() {
+        let mut gilrs = mock_builder(&[], "")
+            .set_dpad_debounce(Duration::from_secs(60))
+            .build()
+            .unwrap();
+
+        let code = Code(nec::AXIS_DPADX);
+        let dpad = |gilrs: &mut Gilrs, val, time| {
+            gilrs.insert_event(Event {
+                id: GamepadId(0),
+                event: EventType::AxisChanged(Axis::DPadX, val, code),
+                time,
+            });
+            let mut events = Vec::new();
+            gilrs.poll_events(&mut events);
+            events
+                .into_iter()
+                .filter_map(|ev| match ev.event {
+                    EventType::ButtonPressed(btn, _) => Some((btn, true)),
+                    EventType::ButtonReleased(btn, _) => Some((btn, false)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        let now = SystemTime::now();
+
+        assert_eq!(dpad(&mut gilrs, -1.0, now), [(Button::DPadLeft, true)]);
+        // Rolling to the other side through center.
+        assert_eq!(dpad(&mut gilrs, 0.0, now), []);
+        assert_eq!(
+            dpad(&mut gilrs, 1.0, now),
+            [(Button::DPadLeft, false), (Button::DPadRight, true)]
+        );
+        // Coming back to the same direction.
+        assert_eq!(dpad(&mut gilrs, 0.0, now), []);
+        assert_eq!(dpad(&mut gilrs, 1.0, now), []);
+        // Centered for longer than window.
+        assert_eq!(
+            dpad(&mut gilrs, 0.0, now - Duration::from_secs(61)),
+            [(Button::DPadRight, false)]
+        );
+        assert!(gilrs
+            .gamepad(GamepadId(0))
+            .state()
+            .buttons()
+            .all(|(_, b)| !b.is_pressed()));
+    }
+
+    #[test]
+    fn clear() {
+        let build = || {
+            let mut gilrs = mock_gilrs(
+                &[
+                    RawEventType::Connected,
+                    RawEventType::ButtonPressed(nec::BTN_SOUTH),
+                    RawEventType::AxisValueChanged(-128, nec::AXIS_LSTICKX),
+                ],
+                "",
+            );
+            while gilrs.next_event().is_some() {}
+            gilrs.insert_event(Event::new(
+                GamepadId(0),
+                EventType::ButtonPressed(Button::LeftThumb, Code(nec::BTN_LTHUMB)),
+            ));
+            gilrs
+        };
+
+        let mut gilrs = build();
+        gilrs.clear(false);
+        assert_eq!(gilrs.next_event(), None);
+        let gamepad = gilrs.gamepad(GamepadId(0));
+        assert!(!gamepad.is_pressed(Button::South));
+        assert_eq!(gamepad.value(Axis::LeftStickX), 0.0);
+
+        let mut gilrs = build();
+        gilrs.clear(true);
+        let mut events = Vec::new();
+        gilrs.poll_events(&mut events);
+        let mut events: Vec<_> = events.into_iter().map(|ev| ev.event).collect();
+        assert_eq!(events.len(), 3);
+        assert!(events.contains(&EventType::ButtonReleased(
+            Button::South,
+            Code(nec::BTN_SOUTH)
+        )));
+        events.retain(|ev| matches!(ev, EventType::AxisChanged(..)));
+        assert_eq!(
+            events,
+            [EventType::AxisChanged(
+                Axis::LeftStickX,
+                0.0,
+                Code(nec::AXIS_LSTICKX)
+            )]
+        );
+        let gamepad = gilrs.gamepad(GamepadId(0));
+        assert!(!gamepad.is_pressed(Button::South));
+        assert!(!gamepad.is_pressed(Button::LeftThumb));
+    }
+
+    #[test]
+    fn power_changed() {
+        let mut gilrs = mock_gilrs(
+            &[RawEventType::PowerChanged(PowerInfo::Discharging(33))],
+            "",
+        );
+
+        assert_eq!(
+            gilrs.next_event().map(|ev| ev.event),
+            Some(EventType::PowerChanged(PowerInfo::Discharging(33)))
+        );
+    }
+
+    #[test]
+    fn player_index_not_supported() {
+        let gilrs = mock_gilrs(&[], "");
+
+        assert!(matches!(
+            gilrs.gamepad(GamepadId(0)).set_player_index(Some(1)),
+            Err(Error::NotSupported)
+        ));
+    }
+
+    #[test]
+    fn brand() {
+        assert_eq!(
+            Brand::detect(Some(0x054c), "Wireless Controller"),
+            Brand::PlayStation
+        );
+        assert_eq!(
+            Brand::detect(Some(0x057e), "Pro Controller"),
+            Brand::Nintendo
+        );
+        // Third party controllers use their own vendor ID.
+        assert_eq!(
+            Brand::detect(Some(0x0e6f), "Xbox Controller"),
+            Brand::Generic
+        );
+        // XInput doesn't report vendor ID.
+        assert_eq!(Brand::detect(None, "Xbox Controller"), Brand::Xbox);
+        assert_eq!(Brand::detect(None, "Mock"), Brand::Generic);
+    }
+
+    #[test]
+    fn power_info_interval() {
+        // Mock backend doesn't refresh power information, setting is ignored.
+        let gilrs = mock_gilrs(&[], "");
+        assert_eq!(gilrs.power_info_interval(), None);
+        let gilrs = mock_builder(&[], "")
+            .set_power_info_interval(Duration::ZERO)
+            .build()
+            .unwrap();
+        assert_eq!(gilrs.power_info_interval(), None);
+    }
+
+    #[test]
+    fn stick_polar() {
+        let mut gilrs = mock_gilrs(
+            &[
+                // Corner, x and y are both 1.0.
+                RawEventType::AxisValueChanged(127, nec::AXIS_LSTICKX),
+                RawEventType::AxisValueChanged(127, nec::AXIS_LSTICKY),
+                // Inside dead zone.
+                RawEventType::AxisValueChanged(5, nec::AXIS_LSTICKX),
+                RawEventType::AxisValueChanged(-5, nec::AXIS_LSTICKY),
+            ],
+            "",
+        );
+
+        let gamepad = gilrs.gamepad(GamepadId(0));
+        assert_eq!(gamepad.stick_magnitude(Side::Left), 0.0);
+        assert_eq!(gamepad.stick_angle(Side::Left), 0.0);
+
+        gilrs.next_event().unwrap();
+        gilrs.next_event().unwrap();
+        let gamepad = gilrs.gamepad(GamepadId(0));
+        assert_eq!(gamepad.stick_magnitude(Side::Left), 1.0);
+        let angle = gamepad.stick_angle(Side::Left).abs();
+        assert!((angle - std::f32::consts::FRAC_PI_4).abs() < 1e-6);
+
+        while gilrs.next_event().is_some() {}
+        let gamepad = gilrs.gamepad(GamepadId(0));
+        assert_eq!(gamepad.stick(Side::Left), (0.0, 0.0));
+        assert_eq!(gamepad.stick_magnitude(Side::Left), 0.0);
+        assert_eq!(gamepad.stick_angle(Side::Left), 0.0);
+    }
+
+    #[test]
+    fn trigger_rumble() {
+        let gilrs = mock_gilrs(&[], "");
+
+        // Backend didn't report trigger motors.
+        let gamepad = gilrs.gamepad(GamepadId(0));
+        assert!(!gamepad.capabilities().has_trigger_rumble);
+        assert!(!gamepad.has_trigger_rumble());
+    }
+
+    #[test]
+    fn calibration_profile() {
+        let mut gilrs = mock_gilrs(
+            &[RawEventType::AxisValueChanged(-128, nec::AXIS_LSTICKX)],
+            "",
+        );
+
+        let mut profile = CalibrationProfile::default();
+        profile.inverted.insert(Axis::LeftStickX);
+        profile.deadzones.insert(Axis::LeftStickX, 0.25);
+        gilrs.apply_profile(GamepadId(0), &profile);
+
+        let gamepad = gilrs.gamepad(GamepadId(0));
+        assert_eq!(gamepad.current_profile(), &profile);
+        assert_eq!(gamepad.deadzone(Code(nec::AXIS_LSTICKX)), Some(0.25));
+
+        // Inverted and rescaled by deadzone filter.
+        match gilrs.next_event().map(|ev| ev.event) {
+            Some(EventType::AxisChanged(Axis::LeftStickX, val, _)) => assert!(val > 0.9),
+            ev => panic!("unexpected event {:?}", ev),
+        }
+    }
+
+    #[test]
+    fn trigger_deadzones() {
+        let mut gilrs = mock_gilrs(&[], "");
+
+        let (left, right) = (Code(nec::BTN_LT2), Code(nec::BTN_RT2));
+        let gamepad = gilrs.gamepad(GamepadId(0));
+        assert_eq!(gamepad.deadzone(left), gamepad.deadzone(right));
+
+        let profile = CalibrationProfile {
+            trigger_deadzones: [Some(0.2); 2],
+            ..Default::default()
+        };
+        gilrs.apply_profile(GamepadId(0), &profile);
+
+        let gamepad = gilrs.gamepad(GamepadId(0));
+        assert_eq!(gamepad.deadzone(left), Some(0.2));
+        assert_eq!(gamepad.deadzone(right), Some(0.2));
+    }
+
+    #[test]
+    fn press_thresholds() {
+        // Pulls of 20%, 40%, 60% and 80%, then release.
+        let pulls = [0.2, 0.4, 0.6, 0.8, 0.0];
+        for (threshold, expected) in [(0.3, 1), (0.5, 2), (0.7, 3), (0.9, pulls.len())] {
+            let events: Vec<_> = pulls
+                .iter()
+                .map(|p| {
+                    RawEventType::AxisValueChanged((p * 255.0) as i32 - 128, nec::AXIS_LSTICKX)
+                })
+                .collect();
+            let mut gilrs = mock_builder(&events, "00000000000000000000000000000000,Mock,a:a0,")
+                .with_default_filters(false)
+                .build()
+                .unwrap();
+            let mut profile = CalibrationProfile::default();
+            profile
+                .press_thresholds
+                .insert(Button::South, (threshold, threshold - 0.1));
+            gilrs.apply_profile(GamepadId(0), &profile);
+
+            let mut changed = 0;
+            let mut pressed_at = pulls.len();
+            while let Some(ev) = gilrs.next_event() {
+                match ev.event {
+                    EventType::ButtonPressed(Button::South, _) => pressed_at = changed,
+                    EventType::ButtonChanged(Button::South, ..) => changed += 1,
+                    _ => (),
+                }
+            }
+            assert_eq!(pressed_at, expected, "threshold {}", threshold);
+            assert!(!gilrs.gamepad(GamepadId(0)).is_pressed(Button::South));
+        }
+    }
+
+    #[test]
+    fn axis_raw_value() {
+        let mut gilrs = mock_gilrs(
+            &[
+                // Inside dead zone.
+                RawEventType::AxisValueChanged(5, nec::AXIS_LSTICKX),
+                RawEventType::AxisValueChanged(127, nec::AXIS_LSTICKX),
+            ],
+            "",
+        );
+
+        // Raw value is stored before filters are applied.
+        assert!(gilrs.next_event_priv(false, None).is_some());
+        let data = *gilrs
+            .gamepad(GamepadId(0))
+            .axis_data(Axis::LeftStickX)
+            .unwrap();
+        assert_eq!(data.raw_value(), 5);
+        assert_eq!(data.value(), 0.0);
+
+        while gilrs.next_event().is_some() {}
+        let gamepad = gilrs.gamepad(GamepadId(0));
+        let data = gamepad.axis_data(Axis::LeftStickX).unwrap();
+        assert_eq!(data.raw_value(), 127);
+        assert_eq!(data.value(), 1.0);
+        assert_eq!(
+            gamepad.axis_code(Axis::LeftStickX),
+            Some(Code(nec::AXIS_LSTICKX))
+        );
+    }
+
+    #[test]
+    fn disabled_gamepad() {
+        let events = [
+            RawEventType::ButtonPressed(nec::BTN_SOUTH),
+            RawEventType::ButtonPressed(nec::BTN_LTHUMB),
+            RawEventType::Disconnected(DisconnectReason::Removed),
+            RawEventType::ButtonReleased(nec::BTN_LTHUMB),
+        ];
+        // Without default filters, which would hide dropped events.
+        let mut gilrs = mock_builder(&events, "")
+            .with_default_filters(false)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            gilrs.next_event().map(|ev| ev.event),
+            Some(EventType::ButtonPressed(
+                Button::South,
+                Code(nec::BTN_SOUTH)
+            ))
+        );
+        assert_eq!(
+            gilrs.next_event().map(|ev| ev.event),
+            Some(EventType::ButtonChanged(
+                Button::South,
+                1.0,
+                Code(nec::BTN_SOUTH)
+            ))
+        );
+
+        gilrs.set_gamepad_enabled(GamepadId(0), false);
+        assert!(!gilrs.gamepad(GamepadId(0)).is_enabled());
+        assert!(!gilrs.gamepad(GamepadId(0)).is_pressed(Button::South));
+        assert_eq!(
+            gilrs.next_event().map(|ev| ev.event),
+            Some(EventType::Disconnected)
+        );
+
+        gilrs.set_gamepad_enabled(GamepadId(0), true);
+        assert_eq!(
+            gilrs.next_event().map(|ev| ev.event),
+            Some(EventType::ButtonReleased(
+                Button::LeftThumb,
+                Code(nec::BTN_LTHUMB)
+            ))
+        );
+    }
+
+    #[test]
+    fn ff_slots_are_unbounded() {
+        let gilrs = mock_gilrs(&[], "");
+        let gamepad = gilrs.gamepad(GamepadId(0));
+        assert_eq!(gamepad.ff_slots_total(), None);
+        assert_eq!(gamepad.ff_slots_free(), None);
+    }
+
+    #[test]
+    fn invalid_backend_events_are_skipped() {
+        let events = [
+            // Gamepad that was never connected.
+            RawEvent::new(3, RawEventType::ButtonPressed(nec::BTN_SOUTH)),
+            RawEvent::new(3, RawEventType::Disconnected(DisconnectReason::Removed)),
+            // Axis without `AxisInfo`.
+            RawEvent::new(0, RawEventType::AxisValueChanged(5, nec::AXIS_RSTICKX)),
+            // Unexpected ID and ID for which backend doesn't have gamepad.
+            RawEvent::new(2, RawEventType::Connected),
+            RawEvent::new(1, RawEventType::Connected),
+            RawEvent::new(0, RawEventType::ButtonPressed(nec::BTN_SOUTH)),
+        ];
+        let mut gilrs = GilrsBuilder::new()
+            .add_included_mappings(false)
+            .add_env_mappings(false)
+            .with_default_filters(false)
+            .with_backend(MockBackend {
+                gamepad: MockGamepad,
+                events: events.into_iter().collect(),
+            })
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            gilrs.next_event().map(|ev| (ev.id, ev.event)),
+            Some((
+                GamepadId(0),
+                EventType::ButtonPressed(Button::South, Code(nec::BTN_SOUTH))
+            ))
+        );
+        assert!(matches!(
+            gilrs.next_event().map(|ev| ev.event),
+            Some(EventType::ButtonChanged(Button::South, ..))
+        ));
+        assert_eq!(gilrs.next_event(), None);
+        assert_eq!(gilrs.len(), 1);
+    }
+
+    #[test]
+    fn replaced_gamepad_doesnt_inherit_settings() {
+        let events = [
+            RawEventType::Disconnected(DisconnectReason::Removed),
+            RawEventType::Connected,
+            RawEventType::Disconnected(DisconnectReason::Replaced),
+            RawEventType::Connected,
+        ];
+        let mut gilrs = mock_gilrs(&events, "");
+        let id = GamepadId(0);
+        let mut profile = CalibrationProfile::default();
+        profile.inverted.insert(Axis::LeftStickY);
+        gilrs.apply_profile(id, &profile);
+        gilrs.set_gamepad_enabled(id, false);
+
+        // Same device was reconnected.
+        assert_eq!(gilrs.next_event().unwrap().event, EventType::Disconnected);
+        assert_eq!(gilrs.next_event().unwrap().event, EventType::Connected);
+        assert_eq!(gilrs.gamepad(id).current_profile(), &profile);
+        assert!(!gilrs.gamepad(id).is_enabled());
+
+        // Other device took its slot.
+        assert_eq!(gilrs.next_event().unwrap().event, EventType::Disconnected);
+        assert_eq!(gilrs.next_event().unwrap().event, EventType::Connected);
+        assert_eq!(
+            gilrs.gamepad(id).current_profile(),
+            &CalibrationProfile::default()
+        );
+        assert!(gilrs.gamepad(id).is_enabled());
+    }
+
+    #[test]
+    fn socd_cleaning() {
+        fn clean(mode: SocdMode, enabled: bool, input: &[(Button, bool)]) -> Vec<(Button, bool)> {
+            // State has to be updated with cleaned events.
+            let mut gilrs = mock_builder(&[], "")
+                .set_update_state(false)
+                .build()
+                .unwrap();
+            gilrs.set_socd_cleaning(GamepadId(0), enabled);
+            let socd = SocdCleaner { mode };
+
+            // All inputs are queued up front, so events produced by one of them have to be
+            // returned before the next input is processed.
+            let start = SystemTime::now();
+            for (i, &(btn, pressed)) in input.iter().enumerate() {
+                let code = Code(match btn {
+                    Button::DPadLeft => nec::BTN_DPAD_LEFT,
+                    Button::DPadRight => nec::BTN_DPAD_RIGHT,
+                    Button::DPadUp => nec::BTN_DPAD_UP,
+                    _ => nec::BTN_DPAD_DOWN,
+                });
+                let event = if pressed {
+                    EventType::ButtonPressed(btn, code)
+                } else {
+                    EventType::ButtonReleased(btn, code)
+                };
+                gilrs.insert_event(Event {
+                    id: GamepadId(0),
+                    event,
+                    time: start + Duration::from_millis(i as u64),
+                });
+            }
+
+            let mut out = Vec::new();
+            let mut last_time = start;
+            while let Some(ev) = gilrs.next_event().filter_ev(&socd, &mut gilrs) {
+                assert!(ev.time >= last_time);
+                last_time = ev.time;
+                gilrs.update(&ev);
+                if enabled {
+                    let gamepad = gilrs.gamepad(ev.id);
+                    let both = |a, b| gamepad.is_pressed(a) && gamepad.is_pressed(b);
+                    assert!(!both(Button::DPadLeft, Button::DPadRight));
+                    assert!(!both(Button::DPadUp, Button::DPadDown));
+                }
+                match ev.event {
+                    EventType::ButtonPressed(btn, _) => out.push((btn, true)),
+                    EventType::ButtonReleased(btn, _) => out.push((btn, false)),
+                    _ => (),
+                }
+            }
+            out
+        }
+
+        use Button::{DPadDown as D, DPadLeft as L, DPadRight as R, DPadUp as U};
+        let horizontal = [(L, true), (R, true), (R, false), (L, false)];
+        let vertical = [(D, true), (U, true), (U, false), (D, false)];
+
+        assert_eq!(clean(SocdMode::Neutral, false, &horizontal), horizontal);
+        assert_eq!(
+            clean(SocdMode::Neutral, true, &horizontal),
+            [(L, true), (L, false), (L, true), (L, false)]
+        );
+        assert_eq!(
+            clean(SocdMode::LastWins, true, &horizontal),
+            [
+                (L, true),
+                (L, false),
+                (R, true),
+                (R, false),
+                (L, true),
+                (L, false)
+            ]
+        );
+        assert_eq!(
+            clean(SocdMode::UpPriority, true, &vertical),
+            [
+                (D, true),
+                (D, false),
+                (U, true),
+                (U, false),
+                (D, true),
+                (D, false)
+            ]
+        );
+        assert_eq!(
+            clean(SocdMode::UpPriority, true, &horizontal),
+            [(L, true), (L, false), (L, true), (L, false)]
+        );
+    }
+
+    #[test]
+    fn stick_to_dpad() {
+        let events = [
+            RawEventType::AxisValueChanged(127, nec::AXIS_LSTICKX),
+            RawEventType::AxisValueChanged(120, nec::AXIS_LSTICKX),
+            RawEventType::AxisValueChanged(0, nec::AXIS_LSTICKX),
+            RawEventType::AxisValueChanged(-128, nec::AXIS_LSTICKX),
+        ];
+        // Filter doesn't depend on state, which isn't updated here.
+        let mut gilrs = mock_builder(&events, "")
+            .set_update_state(false)
+            .build()
+            .unwrap();
+        let stick_to_dpad = StickToDpad::new(Side::Left);
+
+        let mut out = Vec::new();
+        while let Some(ev) = gilrs.next_event().filter_ev(&stick_to_dpad, &mut gilrs) {
+            match ev.event {
+                EventType::ButtonPressed(btn, _) => out.push((btn, true)),
+                EventType::ButtonReleased(btn, _) => out.push((btn, false)),
+                _ => (),
+            }
+        }
+        assert_eq!(
+            out,
+            [
+                (Button::DPadRight, true),
+                (Button::DPadRight, false),
+                (Button::DPadLeft, true)
+            ]
+        );
+    }
+
+    #[test]
+    fn default_filters() {
+        let mut gilrs = mock_gilrs(
+            &[
+                RawEventType::Connected,
+                // Inside dead zone.
+                RawEventType::AxisValueChanged(5, nec::AXIS_LSTICKX),
+                RawEventType::AxisValueChanged(127, nec::AXIS_LSTICKX),
+                // Change smaller than jitter threshold.
+                RawEventType::AxisValueChanged(126, nec::AXIS_LSTICKX),
+            ],
+            "",
+        );
+
+        let mut events = Vec::new();
+        gilrs.poll_events(&mut events);
+        let events: Vec<_> = events.into_iter().map(|ev| ev.event).collect();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0], EventType::Connected);
+        assert!(matches!(
+            events[1],
+            EventType::AxisChanged(Axis::LeftStickX, v, _) if v == 1.0
+        ));
+    }
+
+    #[test]
+    fn manual_update_matches_automatic() {
+        let build = |update_state| {
+            let events = [
+                RawEventType::Connected,
+                RawEventType::ButtonPressed(nec::BTN_SOUTH),
+                RawEventType::AxisValueChanged(-128, nec::AXIS_LSTICKX),
+                RawEventType::ButtonReleased(nec::BTN_SOUTH),
+                RawEventType::AxisValueChanged(100, nec::AXIS_LSTICKX),
+            ];
+            mock_builder(&events, "")
+                .set_update_state(update_state)
+                .build()
+                .unwrap()
+        };
+
+        let mut automatic = build(true);
+        while automatic.next_event().is_some() {}
+
+        let mut manual = build(false);
+        while let Some(ev) = manual.next_event() {
+            manual.update(&ev);
+        }
+
+        let (a, m) = (
+            automatic.gamepad(GamepadId(0)),
+            manual.gamepad(GamepadId(0)),
+        );
+        assert!(!a.is_pressed(Button::South) && !m.is_pressed(Button::South));
+        assert!(a.button_data(Button::South).is_some());
+        assert_eq!(a.value(Axis::LeftStickX), m.value(Axis::LeftStickX));
+        assert_ne!(a.value(Axis::LeftStickX), 0.0);
+        assert_eq!(
+            a.button_data(Button::South).map(|d| d.counter()),
+            m.button_data(Button::South).map(|d| d.counter())
+        );
+    }
+
+    #[test]
+    fn state_updated_before_event_is_returned() {
+        let events = [
+            RawEventType::Connected,
+            RawEventType::ButtonPressed(nec::BTN_SOUTH),
+            RawEventType::AxisValueChanged(-128, nec::AXIS_LSTICKX),
+            RawEventType::ButtonReleased(nec::BTN_SOUTH),
+        ];
+        let mut gilrs = mock_gilrs(&events, "");
+        let id = GamepadId(0);
+
+        let mut checked = 0;
+        let mut check = |gilrs: &mut Gilrs| {
+            while let Some(ev) = gilrs.next_event() {
+                let gamepad = gilrs.gamepad(ev.id);
+                match ev.event {
+                    EventType::ButtonPressed(btn, _) => assert!(gamepad.is_pressed(btn)),
+                    EventType::ButtonReleased(btn, _) => assert!(!gamepad.is_pressed(btn)),
+                    EventType::ButtonChanged(btn, val, _) => {
+                        assert_eq!(gamepad.button_data(btn).map(|d| d.value()), Some(val))
+                    }
+                    EventType::AxisChanged(axis, val, _) => assert_eq!(gamepad.value(axis), val),
+                    _ => continue,
+                }
+                checked += 1;
+            }
+        };
+        check(&mut gilrs);
+
+        // Inserted events too.
+        let code = gilrs.gamepad(id).button_code(Button::LeftThumb).unwrap();
+        gilrs.insert_event(Event::new(
+            id,
+            EventType::ButtonPressed(Button::LeftThumb, code),
+        ));
+        gilrs.insert_event(Event::new(
+            id,
+            EventType::ButtonChanged(Button::LeftThumb, 1.0, code),
+        ));
+        check(&mut gilrs);
+
+        assert_eq!(checked, 7);
+        assert!(gilrs.gamepad(id).is_pressed(Button::LeftThumb));
+    }
+
+    #[test]
+    fn thumb_click_during_stick_movement() {
+        let mut events = vec![RawEventType::Connected];
+        for i in 0..20 {
+            let val = if i % 2 == 0 { -128 } else { 127 };
+            events.push(RawEventType::AxisValueChanged(val, nec::AXIS_LSTICKX));
+            if i == 5 {
+                events.push(RawEventType::ButtonPressed(nec::BTN_LTHUMB));
+            } else if i == 6 {
+                events.push(RawEventType::ButtonReleased(nec::BTN_LTHUMB));
+            } else if i == 10 {
+                events.push(RawEventType::ButtonPressed(nec::BTN_LTHUMB));
+            }
+        }
+        let mut gilrs = mock_gilrs(&events, "");
+
+        let mut events = Vec::new();
+        gilrs.poll_events(&mut events);
+        let clicks: Vec<_> = events
+            .iter()
+            .filter_map(|ev| match ev.event {
+                EventType::ButtonPressed(Button::LeftThumb, _) => Some(true),
+                EventType::ButtonReleased(Button::LeftThumb, _) => Some(false),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(clicks, [true, false, true]);
+        assert!(gilrs.gamepad(GamepadId(0)).is_pressed(Button::LeftThumb));
+        assert_eq!(
+            gilrs
+                .gamepad(GamepadId(0))
+                .button_data(Button::LeftThumb)
+                .map(|d| d.value()),
+            Some(1.0)
+        );
+    }
+
+    #[test]
+    fn event_logger() {
+        let logged = Arc::new(Mutex::new(Vec::new()));
+        let log = logged.clone();
+        let events = [
+            RawEventType::Connected,
+            RawEventType::ButtonPressed(nec::BTN_SOUTH),
+        ];
+        let mut gilrs = mock_builder(&events, "")
+            .set_event_logger(move |id, ev| log.lock().unwrap().push((id, ev.event)))
+            .build()
+            .unwrap();
+
+        let mut events = Vec::new();
+        gilrs.poll_events(&mut events);
+        let events: Vec<_> = events.into_iter().map(|ev| (0, ev.event)).collect();
+        assert_eq!(*logged.lock().unwrap(), events);
+    }
+
+    #[test]
+    fn split_combined_triggers() {
+        let events = [
+            RawEventType::Connected,
+            RawEventType::AxisValueChanged(-128, nec::AXIS_LSTICKX),
+            // Nothing changed.
+            RawEventType::AxisValueChanged(-128, nec::AXIS_LSTICKX),
+            RawEventType::AxisValueChanged(127, nec::AXIS_LSTICKX),
+        ];
+        let mappings = "00000000000000000000000000000000,Mock,lefttrigger:-a0,righttrigger:+a0,";
+        let mut gilrs = mock_builder(&events, mappings)
+            .with_default_filters(false)
+            .build()
+            .unwrap();
+
+        let mut events = Vec::new();
+        gilrs.poll_events(&mut events);
+        let events: Vec<_> = events.into_iter().map(|ev| ev.event).collect();
+        assert!(!events.contains(&EventType::Dropped));
+        let pos = |f: &dyn Fn(&EventType) -> bool| events.iter().position(f).unwrap();
+        let lt_pressed = pos(&|ev| matches!(ev, EventType::ButtonPressed(Button::LeftTrigger2, _)));
+        let lt_released =
+            pos(&|ev| matches!(ev, EventType::ButtonReleased(Button::LeftTrigger2, _)));
+        let rt_pressed =
+            pos(&|ev| matches!(ev, EventType::ButtonPressed(Button::RightTrigger2, _)));
+        assert!(lt_pressed < lt_released && lt_pressed < rt_pressed);
+
+        let gamepad = gilrs.gamepad(GamepadId(0));
+        assert!(!gamepad.is_pressed(Button::LeftTrigger2));
+        assert!(gamepad.is_pressed(Button::RightTrigger2));
+        assert_eq!(gamepad.trigger(Side::Left), 0.0);
+        assert_eq!(gamepad.trigger(Side::Right), 1.0);
+        let raw = |btn| gamepad.button_data(btn).map(|d| d.raw_value());
+        assert_eq!(raw(Button::LeftTrigger2), Some(127));
+        assert_eq!(raw(Button::RightTrigger2), Some(127));
+    }
+
+    #[test]
+    fn split_combined_triggers_without_state_updates() {
+        let events = [
+            RawEventType::AxisValueChanged(-128, nec::AXIS_LSTICKX),
+            RawEventType::AxisValueChanged(0, nec::AXIS_LSTICKX),
+        ];
+        let mappings = "00000000000000000000000000000000,Mock,lefttrigger:-a0,righttrigger:+a0,";
+        let mut gilrs = mock_builder(&events, mappings)
+            .with_default_filters(false)
+            .set_update_state(false)
+            .build()
+            .unwrap();
+
+        // Release is reported even though state still says trigger isn't pulled.
+        let mut events = Vec::new();
+        gilrs.poll_events(&mut events);
+        let changes: Vec<_> = events
+            .into_iter()
+            .filter_map(|ev| match ev.event {
+                EventType::ButtonChanged(Button::LeftTrigger2, val, _) => Some(val),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(changes.len(), 2);
+        assert!(changes[0] > 0.9);
+        assert_eq!(changes[1], 0.0);
+    }
 
     #[test]
     fn axis_value_documented_case() {
@@ -1224,4 +3270,114 @@ mod tests {
         assert_eq!(0., axis_value(&info, 0, axis));
         assert_eq!(0., axis_value(&info, 1, axis));
     }
+
+    #[test]
+    fn garbage_axis_info_gives_finite_values() {
+        let infos = [
+            AxisInfo {
+                min: 0,
+                max: 0,
+                deadzone: None,
+            },
+            AxisInfo {
+                min: 100,
+                max: -100,
+                deadzone: None,
+            },
+            AxisInfo {
+                min: i32::MIN,
+                max: i32::MAX,
+                deadzone: None,
+            },
+            AxisInfo {
+                min: -128,
+                max: 127,
+                deadzone: None,
+            },
+        ];
+        let values = [i32::MIN, -1000, -1, 0, 1, 1000, i32::MAX];
+
+        for info in &infos {
+            for &val in &values {
+                for axis in [Axis::LeftStickX, Axis::LeftStickY] {
+                    let v = axis_value(info, val, axis);
+                    assert!(
+                        v.is_finite() && (-1.0..=1.0).contains(&v),
+                        "{:?} {}",
+                        info,
+                        v
+                    );
+                }
+                let v = btn_value(info, val);
+                assert!(
+                    v.is_finite() && (0.0..=1.0).contains(&v),
+                    "{:?} {}",
+                    info,
+                    v
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn btn_value_overflow() {
+        let info = AxisInfo {
+            min: i32::MIN,
+            max: i32::MAX,
+            deadzone: None,
+        };
+
+        assert_eq!(btn_value(&info, i32::MIN), 0.0);
+        assert_eq!(btn_value(&info, i32::MAX), 1.0);
+    }
+
+    #[test]
+    fn poll_events_drains_queue() {
+        let mut gilrs = GilrsBuilder::new()
+            .with_default_filters(false)
+            .add_included_mappings(false)
+            .build()
+            .unwrap();
+        let id = GamepadId(0);
+        gilrs.insert_event(Event::new(id, EventType::Connected));
+        gilrs.insert_event(Event::new(id, EventType::Disconnected));
+
+        let mut buf = vec![Event::new(id, EventType::Dropped)];
+        assert_eq!(gilrs.poll_events(&mut buf), 2);
+        assert_eq!(buf.len(), 3);
+        assert_eq!(buf[1].event, EventType::Connected);
+        assert_eq!(buf[2].event, EventType::Disconnected);
+
+        assert_eq!(gilrs.poll_events(&mut buf), 0);
+        assert!(gilrs.next_event().is_none());
+    }
+
+    #[test]
+    fn custom_backend() {
+        let events = [
+            RawEventType::Connected,
+            RawEventType::ButtonPressed(nec::BTN_SOUTH),
+            RawEventType::AxisValueChanged(127, nec::AXIS_LSTICKX),
+        ];
+        let mut gilrs = mock_builder(&events, "")
+            .with_default_filters(false)
+            .build()
+            .unwrap();
+
+        let mut events = Vec::new();
+        gilrs.poll_events(&mut events);
+        let events: Vec<_> = events.into_iter().map(|ev| ev.event).collect();
+        assert_eq!(events[0], EventType::Connected);
+        assert!(events
+            .iter()
+            .any(|ev| matches!(ev, EventType::ButtonPressed(Button::South, _))));
+        assert!(events
+            .iter()
+            .any(|ev| matches!(ev, EventType::AxisChanged(Axis::LeftStickX, v, _) if *v == 1.0)));
+
+        let gamepad = gilrs.gamepad(GamepadId(0));
+        assert_eq!(gamepad.name(), "Mock");
+        assert!(gamepad.is_pressed(Button::South));
+        assert_eq!(gamepad.value(Axis::LeftStickX), 1.0);
+    }
 }
@@ -84,6 +84,8 @@
 //!
 //! - `serde-serialize` - enable deriving of serde's `Serialize` and `Deserialize` for
 //!   various types.
+//! - `network` - enable [`network`] module for reading gamepads connected to another machine.
+//!   Implies `serde-serialize`.
 //!
 //! Platform specific notes
 //! ======================
@@ -111,18 +113,22 @@
 #[macro_use]
 extern crate log;
 
-mod constants;
+mod calibration;
 mod gamepad;
 mod mapping;
 mod utils;
 
+pub mod backend;
 pub mod ev;
 pub mod ff;
+#[cfg(feature = "network")]
+pub mod network;
 
+pub use crate::calibration::{CalibrationProfile, ResponseCurve, TriggerCalibration};
 pub use crate::ev::filter::Filter;
-pub use crate::ev::{Axis, Button, Event, EventType};
+pub use crate::ev::{Axis, Button, Event, EventType, Side};
 pub use crate::gamepad::{
-    ConnectedGamepadsIterator, Error, Gamepad, GamepadId, Gilrs, GilrsBuilder, MappingSource,
-    PowerInfo,
+    Capabilities, ConnectedGamepadsIterator, DeviceSubtype, DisconnectReason, Error, Gamepad,
+    GamepadId, Gilrs, GilrsBuilder, MappingSource, PowerInfo,
 };
 pub use crate::mapping::{MappingData as Mapping, MappingError};
@@ -10,8 +10,9 @@ mod parser;
 
 use crate::ev::{self, Axis, AxisOrBtn, Button};
 use crate::utils::PATH_SEPARATOR;
+use gilrs_core::backend::BackendGamepad;
 use gilrs_core::native_ev_codes as nec;
-use gilrs_core::EvCode;
+use gilrs_core::{DeviceSubtype, EvCode};
 
 use std::collections::HashMap;
 use std::env;
@@ -22,7 +23,7 @@ use fnv::FnvHashMap;
 use uuid::Uuid;
 use vec_map::VecMap;
 
-use self::parser::{Error as ParserError, ErrorKind as ParserErrorKind, Parser, Token};
+use self::parser::{AxisRange, Error as ParserError, ErrorKind as ParserErrorKind, Parser, Token};
 
 /// Platform name used by SDL mappings
 #[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd"))]
@@ -48,6 +49,36 @@ pub struct Mapping {
     name: String,
     default: bool,
     hats_mapped: u8,
+    combined_triggers: Option<CombinedTriggers>,
+}
+
+/// Analog triggers reported by single axis, one on each half of its range. Common for older
+/// DirectInput devices.
+///
+/// Each trigger gets its own code in mapping, so both of them can be tracked in `GamepadState`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CombinedTriggers {
+    /// Code of axis reported by device.
+    pub code: EvCode,
+    /// Code used for left trigger.
+    pub left: EvCode,
+    /// Code used for right trigger.
+    pub right: EvCode,
+    /// Left trigger is on positive half of axis.
+    pub left_positive: bool,
+}
+
+impl CombinedTriggers {
+    /// Splits axis value in range [-1.0, 1.0] into left and right trigger values.
+    pub fn split(&self, val: f32) -> (f32, f32) {
+        let (neg, pos) = ((-val).max(0.0), val.max(0.0));
+
+        if self.left_positive {
+            (pos, neg)
+        } else {
+            (neg, pos)
+        }
+    }
 }
 
 impl Mapping {
@@ -57,10 +88,11 @@ impl Mapping {
             name: String::new(),
             default: false,
             hats_mapped: 0,
+            combined_triggers: None,
         }
     }
 
-    pub fn default(gamepad: &gilrs_core::Gamepad) -> Self {
+    pub fn default(gamepad: &dyn BackendGamepad) -> Self {
         use self::Axis as Ax;
         use self::AxisOrBtn::*;
 
@@ -97,6 +129,7 @@ impl Mapping {
             nec::BTN_DPAD_DOWN => Btn(Button::DPadDown),
             nec::BTN_DPAD_LEFT => Btn(Button::DPadLeft),
             nec::BTN_DPAD_RIGHT => Btn(Button::DPadRight),
+            nec::BTN_SHARE => Btn(Button::Share),
 
             nec::AXIS_LT => Btn(Button::LeftTrigger),
             nec::AXIS_RT => Btn(Button::RightTrigger),
@@ -110,9 +143,19 @@ impl Mapping {
             nec::AXIS_RSTICKY => Axis(Ax::RightStickY),
             nec::AXIS_RIGHTZ => Axis(Ax::RightZ),
             nec::AXIS_DPADX => Axis(Ax::DPadX),
-            nec::AXIS_DPADY => Axis(Ax::DPadY)
+            nec::AXIS_DPADY => Axis(Ax::DPadY),
+
+            nec::AXIS_STEERING => Axis(Ax::Steering),
+            nec::AXIS_THROTTLE => Axis(Ax::Throttle),
+            nec::AXIS_BRAKE => Axis(Ax::Brake),
+            nec::AXIS_CLUTCH => Axis(Ax::Clutch)
         ];
 
+        // XInput reports steering of racing wheels as left stick.
+        if gamepad.capabilities().subtype == DeviceSubtype::Wheel {
+            mappings.insert(nec::AXIS_LSTICKX, Axis(Ax::Steering));
+        }
+
         // Remove all mappings that don't have corresponding element in gamepad. Partial fix to #83
         let axes = [
             nec::AXIS_DPADX,
@@ -127,6 +170,10 @@ impl Mapping {
             nec::AXIS_RT,
             nec::AXIS_RT2,
             nec::AXIS_RIGHTZ,
+            nec::AXIS_STEERING,
+            nec::AXIS_THROTTLE,
+            nec::AXIS_BRAKE,
+            nec::AXIS_CLUTCH,
         ];
         let btns = [
             nec::BTN_SOUTH,
@@ -148,6 +195,7 @@ impl Mapping {
             nec::BTN_DPAD_LEFT,
             nec::BTN_DPAD_RIGHT,
             nec::BTN_DPAD_UP,
+            nec::BTN_SHARE,
         ];
 
         for axis in &axes {
@@ -167,6 +215,7 @@ impl Mapping {
             name: String::new(),
             default: true,
             hats_mapped: 0,
+            combined_triggers: None,
         }
     }
 
@@ -181,7 +230,7 @@ impl Mapping {
         name: &str,
         uuid: Uuid,
     ) -> Result<(Self, String), MappingError> {
-        use crate::constants::*;
+        use gilrs_types::constants::*;
 
         if !Self::is_name_valid(name) {
             return Err(MappingError::InvalidName);
@@ -223,6 +272,7 @@ impl Mapping {
                     BTN_DPAD_RIGHT => add_button("dpright", ev_code, Button::DPadRight)?,
                     BTN_C => add_button("c", ev_code, Button::C)?,
                     BTN_Z => add_button("z", ev_code, Button::Z)?,
+                    BTN_SHARE => add_button("misc1", ev_code, Button::Share)?,
                     BTN_UNKNOWN => return Err(MappingError::UnknownElement),
                     _ => unreachable!(),
                 }
@@ -249,6 +299,10 @@ impl Mapping {
                     AXIS_RSTICKY => add_axis("righty", ev_code, Axis::RightStickY)?,
                     AXIS_LEFTZ => add_axis("leftz", ev_code, Axis::LeftZ)?,
                     AXIS_RIGHTZ => add_axis("rightz", ev_code, Axis::RightZ)?,
+                    AXIS_STEERING => add_axis("steering", ev_code, Axis::Steering)?,
+                    AXIS_THROTTLE => add_axis("throttle", ev_code, Axis::Throttle)?,
+                    AXIS_BRAKE => add_axis("brake", ev_code, Axis::Brake)?,
+                    AXIS_CLUTCH => add_axis("clutch", ev_code, Axis::Clutch)?,
                     AXIS_UNKNOWN => return Err(MappingError::UnknownElement),
                     _ => unreachable!(),
                 }
@@ -260,6 +314,7 @@ impl Mapping {
             name: name.to_owned(),
             default: false,
             hats_mapped: 0,
+            combined_triggers: None,
         };
 
         Ok((mapping, sdl_mappings))
@@ -274,6 +329,8 @@ impl Mapping {
         let mut parser = Parser::new(line);
 
         let mut uuid: Option<Uuid> = None;
+        // Axes with half of range mapped to left and right trigger.
+        let mut trigger_halves: [Option<(EvCode, AxisRange)>; 2] = [None, None];
         while let Some(token) = parser.next_token() {
             if let Err(ref e) = token {
                 if e.kind() == &ParserErrorKind::EmptyValue {
@@ -292,9 +349,20 @@ impl Mapping {
                 Token::Uuid(v) => uuid = Some(v),
 
                 Token::Name(name) => mapping.name = name.to_owned(),
-                Token::AxisMapping { from, to, .. } => {
+                Token::AxisMapping {
+                    from, to, input, ..
+                } => {
                     let axis = axes.get(from as usize).cloned();
                     if let Some(axis) = axis {
+                        match to {
+                            AxisOrBtn::Btn(Button::LeftTrigger2) => {
+                                trigger_halves[0] = Some((axis, input))
+                            }
+                            AxisOrBtn::Btn(Button::RightTrigger2) => {
+                                trigger_halves[1] = Some((axis, input))
+                            }
+                            _ => (),
+                        }
                         mapping.mappings.insert(axis, to);
                     } else {
                         warn!(
@@ -368,9 +436,44 @@ impl Mapping {
             }
         }
 
+        if let [Some((left, left_range)), Some((right, right_range))] = trigger_halves {
+            if left == right
+                && left_range != right_range
+                && left_range != AxisRange::Full
+                && right_range != AxisRange::Full
+            {
+                mapping.combine_triggers(left, left_range == AxisRange::UpperHalf, axes);
+            }
+        }
+
         Ok(mapping)
     }
 
+    /// Maps both halves of `code` axis to triggers. Each trigger gets code of `AXIS_LT2` or
+    /// `AXIS_RT2`, unless device already uses it.
+    fn combine_triggers(&mut self, code: EvCode, left_positive: bool, axes: &[EvCode]) {
+        let is_free = |c: &EvCode| *c == code || !axes.contains(c);
+        if !is_free(&nec::AXIS_LT2) || !is_free(&nec::AXIS_RT2) {
+            warn!(
+                "Mapping {}: can't split triggers reported by single axis, codes already used.",
+                self.name
+            );
+            return;
+        }
+
+        self.mappings.remove(&code);
+        self.mappings
+            .insert(nec::AXIS_LT2, AxisOrBtn::Btn(Button::LeftTrigger2));
+        self.mappings
+            .insert(nec::AXIS_RT2, AxisOrBtn::Btn(Button::RightTrigger2));
+        self.combined_triggers = Some(CombinedTriggers {
+            code,
+            left: nec::AXIS_LT2,
+            right: nec::AXIS_RT2,
+            left_positive,
+        });
+    }
+
     fn add_button(
         ident: &str,
         ev_code: EvCode,
@@ -426,6 +529,11 @@ impl Mapping {
     pub fn hats_mapped(&self) -> u8 {
         self.hats_mapped
     }
+
+    /// Returns axis that reports both triggers, if mapping splits one.
+    pub fn combined_triggers(&self) -> Option<CombinedTriggers> {
+        self.combined_triggers
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -507,6 +615,33 @@ impl MappingDb {
         }
     }
 
+    /// Checks syntax of every line of `s`. Empty lines and comments are skipped.
+    pub fn check(s: &str) -> Result<(), MappingError> {
+        for (n, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut parser = Parser::new(line);
+            while let Some(token) = parser.next_token() {
+                let is_valid = match token {
+                    Ok(Token::HatMapping { direction, .. }) => {
+                        matches!(direction, 0 | 1 | 2 | 4 | 8)
+                    }
+                    Ok(_) => true,
+                    Err(e) => e.kind() == &ParserErrorKind::EmptyValue,
+                };
+
+                if !is_valid {
+                    return Err(MappingError::InvalidSdlMapping(n + 1));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn get(&self, uuid: Uuid) -> Option<&str> {
         self.mappings.get(&uuid).map(String::as_ref)
     }
@@ -587,6 +722,8 @@ pub enum MappingError {
     UnknownElement,
     /// `Mapping` have button or axis that are not present in SDL2.
     NotSdl2Compatible,
+    /// SDL mapping on given line (starting from 1) can't be parsed.
+    InvalidSdlMapping(usize),
 }
 
 impl Error for MappingError {}
@@ -609,6 +746,10 @@ impl Display for MappingError {
             }
             MappingError::UnknownElement => "Button::Unknown and Axis::Unknown are not allowed",
             MappingError::NotSdl2Compatible => "one of buttons or axes is not compatible with SDL2",
+            MappingError::InvalidSdlMapping(line) => {
+                sbuf = format!("SDL mapping on line {} is invalid", line);
+                sbuf.as_ref()
+            }
         };
 
         f.write_str(s)
@@ -667,6 +808,121 @@ mod tests {
         Mapping::parse_sdl_mapping(TEST_STR, &BUTTONS, &AXES).unwrap();
     }
 
+    #[test]
+    fn combined_triggers() {
+        let s = "03000000260900008888000000010001,Old Pad,a:b0,b:b1,leftx:a0,lefty:a1,\
+                 lefttrigger:-a2,righttrigger:+a2,";
+        let mapping = Mapping::parse_sdl_mapping(s, &BUTTONS, &AXES[..3]).unwrap();
+        let triggers = mapping.combined_triggers().unwrap();
+
+        assert_eq!(triggers.code, AXES[2]);
+        assert!(!triggers.left_positive);
+        assert_eq!(triggers.split(-0.5), (0.5, 0.0));
+        assert_eq!(triggers.split(1.0), (0.0, 1.0));
+        assert_eq!(mapping.map(&AXES[2]), None);
+        assert_eq!(
+            mapping.map(&triggers.left),
+            Some(AxisOrBtn::Btn(Button::LeftTrigger2))
+        );
+        assert_eq!(
+            mapping.map(&triggers.right),
+            Some(AxisOrBtn::Btn(Button::RightTrigger2))
+        );
+
+        // Device already uses codes that would be assigned to triggers.
+        let mapping = Mapping::parse_sdl_mapping(s, &BUTTONS, &AXES).unwrap();
+        assert_eq!(mapping.combined_triggers(), None);
+
+        // Separate axes are not combined.
+        let mapping = Mapping::parse_sdl_mapping(TEST_STR, &BUTTONS, &AXES).unwrap();
+        assert_eq!(mapping.combined_triggers(), None);
+    }
+
+    #[derive(Debug)]
+    struct Wheel;
+
+    impl BackendGamepad for Wheel {
+        fn name(&self) -> &str {
+            "Wheel"
+        }
+
+        fn is_connected(&self) -> bool {
+            true
+        }
+
+        fn uuid(&self) -> [u8; 16] {
+            [0; 16]
+        }
+
+        fn buttons(&self) -> &[EvCode] {
+            &[]
+        }
+
+        fn axes(&self) -> &[EvCode] {
+            &[
+                nec::AXIS_LSTICKX,
+                nec::AXIS_THROTTLE,
+                nec::AXIS_BRAKE,
+                nec::AXIS_CLUTCH,
+            ]
+        }
+
+        fn axis_info(&self, _nec: EvCode) -> Option<&gilrs_core::AxisInfo> {
+            None
+        }
+
+        fn capabilities(&self) -> gilrs_core::Capabilities {
+            let mut caps = gilrs_core::Capabilities::default();
+            caps.subtype = DeviceSubtype::Wheel;
+            caps
+        }
+    }
+
+    #[test]
+    fn wheel_axes() {
+        let mapping = Mapping::default(&Wheel);
+        let axis = |code| mapping.map(&code);
+        assert_eq!(
+            axis(nec::AXIS_LSTICKX),
+            Some(AxisOrBtn::Axis(Axis::Steering))
+        );
+        assert_eq!(
+            axis(nec::AXIS_THROTTLE),
+            Some(AxisOrBtn::Axis(Axis::Throttle))
+        );
+        assert_eq!(axis(nec::AXIS_BRAKE), Some(AxisOrBtn::Axis(Axis::Brake)));
+        assert_eq!(axis(nec::AXIS_CLUTCH), Some(AxisOrBtn::Axis(Axis::Clutch)));
+
+        let s = "03000000260900008888000000010001,Wheel,steering:a0,throttle:a1,clutch:a2,";
+        let mapping = Mapping::parse_sdl_mapping(s, &BUTTONS, &AXES).unwrap();
+        assert_eq!(mapping.map(&AXES[0]), Some(AxisOrBtn::Axis(Axis::Steering)));
+        assert_eq!(mapping.map(&AXES[2]), Some(AxisOrBtn::Axis(Axis::Clutch)));
+
+        let mut data = MappingData::new();
+        data.insert_axis(ev::Code(AXES[2]), Axis::Clutch);
+        let (mappings, sdl_mapping) =
+            Mapping::from_data(&data, &BUTTONS, &AXES, "Wheel", Uuid::nil()).unwrap();
+        assert!(sdl_mapping.contains("clutch:a2,"));
+        assert_eq!(mappings.map(&AXES[2]), Some(AxisOrBtn::Axis(Axis::Clutch)));
+    }
+
+    #[test]
+    fn share_button() {
+        let s =
+            "03000000260900008888000000010001,Series Pad,a:b0,b:b1,misc1:b14,leftx:a0,lefty:a1,";
+        let mapping = Mapping::parse_sdl_mapping(s, &BUTTONS, &AXES).unwrap();
+        assert_eq!(
+            mapping.map(&BUTTONS[14]),
+            Some(AxisOrBtn::Btn(Button::Share))
+        );
+
+        let mut data = MappingData::new();
+        data.insert_btn(ev::Code(BUTTONS[14]), Button::Share);
+        let (_, sdl_mapping) =
+            Mapping::from_data(&data, &BUTTONS, &AXES, "Series Pad", Uuid::nil()).unwrap();
+        assert!(sdl_mapping.contains("misc1:b14,"));
+    }
+
     #[test]
     fn from_data() {
         let uuid = Uuid::nil();
@@ -712,6 +968,23 @@ mod tests {
         assert_eq!(Err(MappingError::UnknownElement), incorrect_mappings);
     }
 
+    #[test]
+    fn check_mappings() {
+        assert_eq!(MappingDb::check(""), Ok(()));
+        assert_eq!(
+            MappingDb::check(&format!("# Comment\n\n{}\n", TEST_STR)),
+            Ok(())
+        );
+        assert_eq!(
+            MappingDb::check(&format!("{}\nThis is not mapping", TEST_STR)),
+            Err(MappingError::InvalidSdlMapping(2))
+        );
+        assert_eq!(
+            MappingDb::check("03000000260900008888000000010001,Pad,a:b0,foo:b1,"),
+            Err(MappingError::InvalidSdlMapping(1))
+        );
+    }
+
     #[test]
     fn with_mappings() {
         let mappings = format!(
@@ -12,11 +12,13 @@ use uuid::Uuid;
 use crate::ev::{Axis, AxisOrBtn, Button};
 
 // Must be sorted!
-static AXES_SDL: [&str; 31] = [
+static AXES_SDL: [&str; 35] = [
     "a",
     "b",
     "back",
+    "brake",
     "c",
+    "clutch",
     "dpdown",
     "dpleft",
     "dpright",
@@ -40,16 +42,20 @@ static AXES_SDL: [&str; 31] = [
     "righty",
     "rightz",
     "start",
+    "steering",
+    "throttle",
     "touchpad",
     "x",
     "y",
     "z",
 ];
-static AXES: [AxisOrBtn; 31] = [
+static AXES: [AxisOrBtn; 35] = [
     AxisOrBtn::Btn(Button::South),
     AxisOrBtn::Btn(Button::East),
     AxisOrBtn::Btn(Button::Select),
+    AxisOrBtn::Axis(Axis::Brake),
     AxisOrBtn::Btn(Button::C),
+    AxisOrBtn::Axis(Axis::Clutch),
     AxisOrBtn::Btn(Button::DPadDown),
     AxisOrBtn::Btn(Button::DPadLeft),
     AxisOrBtn::Btn(Button::DPadRight),
@@ -61,7 +67,7 @@ static AXES: [AxisOrBtn; 31] = [
     AxisOrBtn::Axis(Axis::LeftStickX),
     AxisOrBtn::Axis(Axis::LeftStickY),
     AxisOrBtn::Axis(Axis::LeftZ),
-    AxisOrBtn::Btn(Button::Unknown),
+    AxisOrBtn::Btn(Button::Share),
     AxisOrBtn::Btn(Button::Unknown),
     AxisOrBtn::Btn(Button::Unknown),
     AxisOrBtn::Btn(Button::Unknown),
@@ -73,6 +79,8 @@ static AXES: [AxisOrBtn; 31] = [
     AxisOrBtn::Axis(Axis::RightStickY),
     AxisOrBtn::Axis(Axis::RightZ),
     AxisOrBtn::Btn(Button::Start),
+    AxisOrBtn::Axis(Axis::Steering),
+    AxisOrBtn::Axis(Axis::Throttle),
     AxisOrBtn::Btn(Button::Unknown),
     AxisOrBtn::Btn(Button::West),
     AxisOrBtn::Btn(Button::North),
@@ -311,7 +319,7 @@ pub enum Token<'a> {
 }
 
 #[repr(u8)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AxisRange {
     LowerHalf,
     UpperHalf,
@@ -0,0 +1,532 @@
+// Copyright 2016-2018 Mateusz Sieczko and other GilRs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Reading gamepads connected to another machine.
+//!
+//! [`EventSender`] writes events of local `Gilrs` to a socket (or any other `Write`).
+//! [`NetworkBackend`] reads them on the other side and presents remote gamepads as local ones, so
+//! they go through normal mapping, filters and state cache.
+//!
+//! ```no_run
+//! use std::net::TcpListener;
+//! use gilrs::network::{EventSender, NetworkBackend};
+//! use gilrs::{Gilrs, GilrsBuilder};
+//!
+//! // On machine with gamepads.
+//! let mut gilrs = Gilrs::new().unwrap();
+//! let (stream, _) = TcpListener::bind("0.0.0.0:7777").unwrap().accept().unwrap();
+//! let mut sender = EventSender::new(stream, &gilrs).unwrap();
+//! while let Some(event) = gilrs.next_event_blocking(None) {
+//!     if sender.send(&gilrs, &event).is_err() {
+//!         break;
+//!     }
+//! }
+//!
+//! // In the game.
+//! let backend = NetworkBackend::connect("192.168.0.2:7777").unwrap();
+//! let mut gilrs = GilrsBuilder::new().with_backend(backend).build().unwrap();
+//! ```
+//!
+//! Events are sent after mapping, so the game sees the same buttons and axes even if it runs on
+//! different platform. The wire format is line based text: a `gilrs-events 1` header followed by
+//! one JSON object per line, with `Event` in its `serde` representation. Platform specific
+//! [`Code`](crate::ev::Code)s are sent too, but ignored by `NetworkBackend`.
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use gilrs_core::backend::{BackendGamepad, GamepadBackend};
+use gilrs_core::{
+    native_ev_codes as nec, AxisInfo, DisconnectReason, EvCode, Event as RawEvent,
+    EventType as RawEventType,
+};
+
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+
+use crate::ev::Code;
+use crate::{Axis, Button, Event, EventType, GamepadId, Gilrs};
+
+const HEADER: &str = "gilrs-events 1";
+
+// Not a valid SDL2 UUID, so included mappings won't match remote gamepads.
+const UUID: [u8; 16] = *b"gilrs-network\0\0\0";
+
+static BUTTONS: [EvCode; 18] = [
+    nec::BTN_SOUTH,
+    nec::BTN_EAST,
+    nec::BTN_NORTH,
+    nec::BTN_WEST,
+    nec::BTN_C,
+    nec::BTN_Z,
+    nec::BTN_LT,
+    nec::BTN_RT,
+    nec::BTN_SELECT,
+    nec::BTN_START,
+    nec::BTN_MODE,
+    nec::BTN_LTHUMB,
+    nec::BTN_RTHUMB,
+    nec::BTN_DPAD_UP,
+    nec::BTN_DPAD_DOWN,
+    nec::BTN_DPAD_LEFT,
+    nec::BTN_DPAD_RIGHT,
+    nec::BTN_SHARE,
+];
+
+static AXES: [EvCode; 14] = [
+    nec::AXIS_LSTICKX,
+    nec::AXIS_LSTICKY,
+    nec::AXIS_LEFTZ,
+    nec::AXIS_RSTICKX,
+    nec::AXIS_RSTICKY,
+    nec::AXIS_RIGHTZ,
+    nec::AXIS_DPADX,
+    nec::AXIS_DPADY,
+    nec::AXIS_LT2,
+    nec::AXIS_RT2,
+    nec::AXIS_STEERING,
+    nec::AXIS_THROTTLE,
+    nec::AXIS_BRAKE,
+    nec::AXIS_CLUTCH,
+];
+
+// Symmetric range, so -1.0, 0.0 and 1.0 survive the round trip exactly.
+static AXIS_INFO: AxisInfo = AxisInfo {
+    min: -32767,
+    max: 32767,
+    deadzone: Some(0),
+};
+
+static TRIGGER_INFO: AxisInfo = AxisInfo {
+    min: 0,
+    max: u16::MAX as i32,
+    deadzone: Some(0),
+};
+
+/// Single line of the wire format.
+#[derive(Debug, Serialize)]
+struct Message<'a> {
+    event: &'a Event,
+    /// Name of gamepad, only sent with `Connected` event.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<&'a str>,
+}
+
+impl<'a> Message<'a> {
+    /// Returns `None` for events that don't describe gamepad state. `name` is only sent with
+    /// `Connected` event.
+    fn new(event: &'a Event, name: &'a str) -> Option<Self> {
+        let name = match event.event {
+            EventType::Connected => Some(name),
+            EventType::Disconnected => None,
+            EventType::ButtonPressed(btn, _)
+            | EventType::ButtonReleased(btn, _)
+            | EventType::ButtonChanged(btn, _, _)
+                if btn != Button::Unknown =>
+            {
+                None
+            }
+            EventType::AxisChanged(axis, _, _) if axis != Axis::Unknown => None,
+            _ => return None,
+        };
+
+        Some(Message { event, name })
+    }
+
+    fn write_to(&self, buf: &mut Vec<u8>) -> io::Result<()> {
+        serde_json::to_writer(&mut *buf, self)?;
+        buf.push(b'\n');
+        Ok(())
+    }
+}
+
+/// `Message` as read by `NetworkBackend`.
+#[derive(Debug, Deserialize)]
+struct RemoteMessage {
+    event: RemoteEvent,
+    #[serde(default)]
+    name: Option<String>,
+}
+
+/// `Event` without fields that only make sense on the machine that sent it.
+#[derive(Debug, Deserialize)]
+struct RemoteEvent {
+    id: GamepadId,
+    event: RemoteEventType,
+}
+
+/// Variants of `EventType` sent by `EventSender`. `Code` is platform specific, so it's ignored.
+#[derive(Debug, Deserialize)]
+enum RemoteEventType {
+    ButtonPressed(Button, IgnoredAny),
+    ButtonReleased(Button, IgnoredAny),
+    ButtonChanged(Button, f32, IgnoredAny),
+    AxisChanged(Axis, f32, IgnoredAny),
+    Connected,
+    Disconnected,
+}
+
+/// Writes events of local gamepads so they can be read by [`NetworkBackend`].
+#[derive(Debug)]
+pub struct EventSender<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> EventSender<W> {
+    /// Creates sender and announces all gamepads that are currently connected to `gilrs`.
+    pub fn new(mut writer: W, gilrs: &Gilrs) -> io::Result<Self> {
+        let mut buf = format!("{}\n", HEADER).into_bytes();
+        for (id, gamepad) in gilrs.gamepads() {
+            let event = Event::new(id, EventType::Connected);
+            if let Some(msg) = Message::new(&event, gamepad.name()) {
+                msg.write_to(&mut buf)?;
+            }
+        }
+        writer.write_all(&buf)?;
+        writer.flush()?;
+
+        Ok(EventSender { writer })
+    }
+
+    /// Sends `event`. Events that don't describe gamepad state (like `Dropped` or
+    /// `ButtonRepeated`) are skipped. `gilrs` is used to get name of newly connected gamepad.
+    pub fn send(&mut self, gilrs: &Gilrs, event: &Event) -> io::Result<()> {
+        let gamepad = gilrs.get_gamepad(event.id);
+        let name = gamepad.as_ref().map_or("", |gamepad| gamepad.name());
+        let msg = match Message::new(event, name) {
+            Some(msg) => msg,
+            None => return Ok(()),
+        };
+        let mut buf = Vec::new();
+        msg.write_to(&mut buf)?;
+        self.writer.write_all(&buf)?;
+        self.writer.flush()
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[derive(Debug)]
+struct RemoteGamepad {
+    name: String,
+    is_connected: bool,
+}
+
+impl BackendGamepad for RemoteGamepad {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn is_connected(&self) -> bool {
+        self.is_connected
+    }
+
+    fn uuid(&self) -> [u8; 16] {
+        UUID
+    }
+
+    fn buttons(&self) -> &[EvCode] {
+        &BUTTONS
+    }
+
+    fn axes(&self) -> &[EvCode] {
+        &AXES
+    }
+
+    fn axis_info(&self, nec: EvCode) -> Option<&AxisInfo> {
+        if nec == nec::AXIS_LT2 || nec == nec::AXIS_RT2 {
+            Some(&TRIGGER_INFO)
+        } else if AXES.contains(&nec) {
+            Some(&AXIS_INFO)
+        } else {
+            None
+        }
+    }
+}
+
+/// Backend that reads events written by [`EventSender`].
+///
+/// Remote gamepads get local IDs in order in which they were first seen. When connection is
+/// closed, all remote gamepads are reported as disconnected.
+#[derive(Debug)]
+pub struct NetworkBackend {
+    rx: Receiver<RemoteMessage>,
+    gamepads: Vec<RemoteGamepad>,
+    ids: HashMap<usize, usize>,
+}
+
+impl NetworkBackend {
+    /// Connects to `EventSender` listening on `addr`.
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_nodelay(true)?;
+
+        Ok(Self::new(stream))
+    }
+
+    /// Creates backend that reads events from `reader` on separate thread.
+    pub fn new<R: Read + Send + 'static>(reader: R) -> Self {
+        let (tx, rx) = mpsc::channel();
+
+        thread::Builder::new()
+            .name("gilrs network".to_owned())
+            .spawn(move || {
+                let mut lines = BufReader::new(reader).lines();
+                match lines.next() {
+                    Some(Ok(header)) if header == HEADER => (),
+                    Some(Ok(header)) => {
+                        error!("Unsupported event stream: {:?}", header);
+                        return;
+                    }
+                    _ => return,
+                }
+
+                for line in lines {
+                    let line = match line {
+                        Ok(line) => line,
+                        Err(e) => {
+                            debug!("Failed to read event stream: {}", e);
+                            break;
+                        }
+                    };
+                    match serde_json::from_str(&line) {
+                        Ok(msg) => {
+                            if tx.send(msg).is_err() {
+                                break;
+                            }
+                        }
+                        Err(e) => warn!("Ignoring invalid event {:?}: {}", line, e),
+                    }
+                }
+            })
+            .expect("failed to spawn thread");
+
+        NetworkBackend {
+            rx,
+            gamepads: Vec::new(),
+            ids: HashMap::new(),
+        }
+    }
+
+    fn handle_message(&mut self, msg: RemoteMessage) -> Option<RawEvent> {
+        let remote_id = usize::from(msg.event.id);
+
+        if let RemoteEventType::Connected = msg.event.event {
+            let id = *self.ids.entry(remote_id).or_insert(self.gamepads.len());
+            if id == self.gamepads.len() {
+                self.gamepads.push(RemoteGamepad {
+                    name: String::new(),
+                    is_connected: false,
+                });
+            }
+            let gamepad = &mut self.gamepads[id];
+            if gamepad.is_connected {
+                return None;
+            }
+            gamepad.name = msg.name.unwrap_or_default();
+            gamepad.is_connected = true;
+
+            return Some(RawEvent::new(id, RawEventType::Connected));
+        }
+
+        let id = *self.ids.get(&remote_id)?;
+        let gamepad = &mut self.gamepads[id];
+        if !gamepad.is_connected {
+            return None;
+        }
+
+        let event = match msg.event.event {
+            RemoteEventType::Connected => unreachable!(),
+            RemoteEventType::Disconnected => {
+                gamepad.is_connected = false;
+                RawEventType::Disconnected(DisconnectReason::Removed)
+            }
+            // Analog triggers are sent as axes, pressed and released events are generated from
+            // their value.
+            RemoteEventType::ButtonPressed(Button::LeftTrigger2 | Button::RightTrigger2, _)
+            | RemoteEventType::ButtonReleased(Button::LeftTrigger2 | Button::RightTrigger2, _) => {
+                return None
+            }
+            RemoteEventType::ButtonPressed(btn, _) => {
+                RawEventType::ButtonPressed(Code::from_button(btn)?.0)
+            }
+            RemoteEventType::ButtonReleased(btn, _) => {
+                RawEventType::ButtonReleased(Code::from_button(btn)?.0)
+            }
+            RemoteEventType::ButtonChanged(btn, val, _) => {
+                let nec = match btn {
+                    Button::LeftTrigger2 => nec::AXIS_LT2,
+                    Button::RightTrigger2 => nec::AXIS_RT2,
+                    _ => return None,
+                };
+                let val = (val.clamp(0.0, 1.0) * TRIGGER_INFO.max as f32).round();
+                RawEventType::AxisValueChanged(val as i32, nec)
+            }
+            RemoteEventType::AxisChanged(axis, val, _) => {
+                let nec = axis_to_nec(axis)?;
+                let mut val = (val.clamp(-1.0, 1.0) * AXIS_INFO.max as f32).round() as i32;
+                // Undo what `Gilrs` will do with raw value of Y axes on this platform.
+                if gilrs_core::IS_Y_AXIS_REVERSED
+                    && matches!(axis, Axis::LeftStickY | Axis::RightStickY | Axis::DPadY)
+                {
+                    val = -val;
+                }
+                RawEventType::AxisValueChanged(val, nec)
+            }
+        };
+
+        Some(RawEvent::new(id, event))
+    }
+
+    /// Reports one remote gamepad as disconnected after connection was closed.
+    fn disconnect_next(&mut self) -> Option<RawEvent> {
+        let id = self.gamepads.iter().position(|gp| gp.is_connected)?;
+        self.gamepads[id].is_connected = false;
+
+        Some(RawEvent::new(
+            id,
+            RawEventType::Disconnected(DisconnectReason::Unknown),
+        ))
+    }
+}
+
+impl GamepadBackend for NetworkBackend {
+    fn next_event(&mut self) -> Option<RawEvent> {
+        loop {
+            match self.rx.try_recv() {
+                Ok(msg) => {
+                    if let Some(ev) = self.handle_message(msg) {
+                        return Some(ev);
+                    }
+                }
+                Err(TryRecvError::Empty) => return None,
+                Err(TryRecvError::Disconnected) => return self.disconnect_next(),
+            }
+        }
+    }
+
+    fn next_event_blocking(&mut self, timeout: Option<Duration>) -> Option<RawEvent> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+        loop {
+            let msg = match deadline {
+                Some(deadline) => {
+                    match self
+                        .rx
+                        .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                    {
+                        Ok(msg) => msg,
+                        Err(RecvTimeoutError::Timeout) => return None,
+                        Err(RecvTimeoutError::Disconnected) => return self.disconnect_next(),
+                    }
+                }
+                None => match self.rx.recv() {
+                    Ok(msg) => msg,
+                    Err(_) => return self.disconnect_next(),
+                },
+            };
+
+            if let Some(ev) = self.handle_message(msg) {
+                return Some(ev);
+            }
+        }
+    }
+
+    fn gamepad(&self, id: usize) -> Option<&dyn BackendGamepad> {
+        self.gamepads.get(id).map(|gp| gp as &dyn BackendGamepad)
+    }
+
+    fn last_gamepad_hint(&self) -> usize {
+        self.gamepads.len()
+    }
+}
+
+fn axis_to_nec(axis: Axis) -> Option<EvCode> {
+    match axis {
+        Axis::LeftStickX => Some(nec::AXIS_LSTICKX),
+        Axis::LeftStickY => Some(nec::AXIS_LSTICKY),
+        Axis::LeftZ => Some(nec::AXIS_LEFTZ),
+        Axis::RightStickX => Some(nec::AXIS_RSTICKX),
+        Axis::RightStickY => Some(nec::AXIS_RSTICKY),
+        Axis::RightZ => Some(nec::AXIS_RIGHTZ),
+        Axis::DPadX => Some(nec::AXIS_DPADX),
+        Axis::DPadY => Some(nec::AXIS_DPADY),
+        Axis::Steering => Some(nec::AXIS_STEERING),
+        Axis::Throttle => Some(nec::AXIS_THROTTLE),
+        Axis::Brake => Some(nec::AXIS_BRAKE),
+        Axis::Clutch => Some(nec::AXIS_CLUTCH),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Message, NetworkBackend, HEADER};
+    use crate::ev::Code;
+    use crate::{Axis, Button, Event, EventType, GamepadId, GilrsBuilder};
+    use gilrs_core::native_ev_codes as nec;
+    use std::io::Cursor;
+    use std::time::Duration;
+
+    #[test]
+    fn remote_gamepad() {
+        let id = GamepadId(7);
+        let events = [
+            EventType::Connected,
+            EventType::ButtonPressed(Button::South, Code(nec::BTN_SOUTH)),
+            EventType::ButtonRepeated(Button::South, Code(nec::BTN_SOUTH)),
+            EventType::AxisChanged(Axis::LeftStickY, 1.0, Code(nec::AXIS_LSTICKY)),
+            EventType::AxisChanged(Axis::Clutch, 1.0, Code(nec::AXIS_CLUTCH)),
+            EventType::ButtonChanged(Button::RightTrigger2, 1.0, Code(nec::BTN_RT2)),
+        ];
+        let mut stream = format!("{}\n", HEADER).into_bytes();
+        for event in events {
+            let event = Event::new(id, event);
+            if let Some(msg) = Message::new(&event, "Remote Pad") {
+                msg.write_to(&mut stream).unwrap();
+            }
+        }
+        stream.extend_from_slice(b"garbage\n");
+
+        let mut gilrs = GilrsBuilder::new()
+            .with_default_filters(false)
+            .add_included_mappings(false)
+            .add_env_mappings(false)
+            .with_backend(NetworkBackend::new(Cursor::new(stream)))
+            .build()
+            .unwrap();
+
+        let mut events = Vec::new();
+        while let Some(ev) = gilrs.next_event_blocking(Some(Duration::from_secs(5))) {
+            let disconnected = ev.event == EventType::Disconnected;
+            events.push(ev);
+            if disconnected {
+                break;
+            }
+        }
+
+        let id = GamepadId(0);
+        assert!(events.iter().all(|ev| ev.id == id));
+        assert_eq!(events[0].event, EventType::Connected);
+        assert_eq!(events.last().unwrap().event, EventType::Disconnected);
+
+        let gamepad = gilrs.gamepad(id);
+        assert_eq!(gamepad.name(), "Remote Pad");
+        assert!(gamepad.is_pressed(Button::South));
+        assert!(gamepad.is_pressed(Button::RightTrigger2));
+        assert_eq!(gamepad.value(Axis::LeftStickY), 1.0);
+        assert_eq!(gamepad.value(Axis::Clutch), 1.0);
+        assert!(!gamepad.is_connected());
+    }
+}
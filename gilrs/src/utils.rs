@@ -20,6 +20,15 @@ pub fn clamp(x: f32, min: f32, max: f32) -> f32 {
     x.clamp(min, max)
 }
 
+/// Like `clamp()`, but NaN is treated as 0.0.
+pub fn clamp_finite(x: f32, min: f32, max: f32) -> f32 {
+    if x.is_nan() {
+        clamp(0.0, min, max)
+    } else {
+        clamp(x, min, max)
+    }
+}
+
 #[cfg(path_separator = "backslash")]
 macro_rules! PATH_SEPARATOR {
     () => {
@@ -46,4 +55,13 @@ mod tests {
         assert_eq!(clamp(0.5, 0.0, 1.0), 0.5);
         assert_eq!(clamp(2.0, 0.0, 1.0), 1.0);
     }
+
+    #[test]
+    fn t_clamp_finite() {
+        assert_eq!(clamp_finite(f32::NAN, -1.0, 1.0), 0.0);
+        assert_eq!(clamp_finite(f32::NAN, 0.5, 1.0), 0.5);
+        assert_eq!(clamp_finite(f32::INFINITY, -1.0, 1.0), 1.0);
+        assert_eq!(clamp_finite(f32::NEG_INFINITY, 0.0, 1.0), 0.0);
+        assert_eq!(clamp_finite(0.25, 0.0, 1.0), 0.25);
+    }
 }